@@ -0,0 +1,217 @@
+//! Ожидание завершения дочернего процесса с ограничением по времени и сбор потребленных им ресурсов.
+
+use std::io;
+use std::mem::MaybeUninit;
+use std::os::unix::process::ExitStatusExt;
+use std::process::{Child, ExitStatus};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+/// Время между `SIGTERM` и `SIGKILL` при принудительном завершении программы.
+const GRACE_PERIOD: Duration = Duration::from_millis(500);
+
+/// Результат ожидания дочернего процесса.
+#[derive(Debug)]
+pub struct Exit {
+    /// Статус завершения.
+    pub status: ExitStatus,
+    /// Процесс был завершен из-за превышения ограничения по времени.
+    pub timed_out: bool,
+    /// Процессорное время в пользовательском режиме.
+    pub user: Duration,
+    /// Процессорное время в режиме ядра.
+    pub sys: Duration,
+    /// Пиковый размер резидентной памяти в КиБ.
+    pub rss: u64,
+}
+
+impl Exit {
+    /// Создает [`Exit`] по статусу и статистике ресурсов `wait4`.
+    fn new(status: ExitStatus, usage: &libc::rusage, timed_out: bool) -> Self {
+        //На macOS ru_maxrss измеряется в байтах, на Linux - в КиБ
+        let rss = if cfg!(target_os = "macos") {
+            usage.ru_maxrss as u64 / 1024
+        } else {
+            usage.ru_maxrss as u64
+        };
+
+        Exit {
+            status,
+            timed_out,
+            user: to_duration(usage.ru_utime),
+            sys: to_duration(usage.ru_stime),
+            rss,
+        }
+    }
+}
+
+/// Ожидает завершения `child`. Если задан `timeout` и процесс не завершился вовремя,
+/// отправляет ему `SIGTERM`, а спустя [`GRACE_PERIOD`] - `SIGKILL`.
+/// Ожидание выполняется блокирующими вызовами, поэтому не вносит погрешность в замер времени.
+/// Процесс освобождается `wait4` только после завершения сторожевого потока,
+/// поэтому сигнал никогда не отправляется чужому процессу с тем же `pid`.
+pub fn wait(child: &mut Child, timeout: Option<Duration>) -> io::Result<Exit> {
+    let pid = child.id() as libc::pid_t;
+    let timeout = match timeout {
+        Some(timeout) => timeout,
+        None => {
+            let (status, usage) = wait4(pid)?;
+            return Ok(Exit::new(status, &usage, false));
+        }
+    };
+
+    let (done, done_rx) = mpsc::channel::<()>();
+    let watchdog = thread::spawn(move || match done_rx.recv_timeout(timeout) {
+        //Процесс мог завершиться одновременно с таймаутом
+        Err(RecvTimeoutError::Timeout) if !matches!(waitid(pid, libc::WNOHANG), Ok(true)) => {
+            signal(pid, libc::SIGTERM);
+            if let Err(RecvTimeoutError::Timeout) = done_rx.recv_timeout(GRACE_PERIOD) {
+                signal(pid, libc::SIGKILL);
+            }
+            true
+        }
+        _ => false,
+    });
+
+    //Процесс остается зомби, пока сторожевой поток может отправить ему сигнал
+    let exited = waitid(pid, 0);
+    //Канал может быть уже закрыт, если сторожевой поток завершился по таймауту
+    let _ = done.send(());
+    let timed_out = watchdog.join().expect("watchdog thread panicked");
+
+    exited?;
+    let (status, usage) = wait4(pid)?;
+    Ok(Exit::new(status, &usage, timed_out))
+}
+
+/// Ожидание завершения процесса `pid` без его освобождения (`WNOWAIT`).
+/// С `options` = `WNOHANG` не блокируется. Возвращает `true`, если процесс завершился.
+fn waitid(pid: libc::pid_t, options: libc::c_int) -> io::Result<bool> {
+    let mut info = MaybeUninit::<libc::siginfo_t>::zeroed();
+
+    loop {
+        let result = unsafe {
+            libc::waitid(
+                libc::P_PID,
+                pid as libc::id_t,
+                info.as_mut_ptr(),
+                libc::WEXITED | libc::WNOWAIT | options,
+            )
+        };
+        if result != -1 {
+            break;
+        }
+
+        let error = io::Error::last_os_error();
+        if error.kind() != io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
+
+    //При WNOHANG и работающем процессе структура остается нулевой
+    Ok(unsafe { info.assume_init().si_pid() } != 0)
+}
+
+/// Блокирующее ожидание процесса `pid` с получением статистики потребленных ресурсов.
+fn wait4(pid: libc::pid_t) -> io::Result<(ExitStatus, libc::rusage)> {
+    let mut status = 0;
+    let mut usage = MaybeUninit::<libc::rusage>::zeroed();
+
+    loop {
+        let result = unsafe { libc::wait4(pid, &mut status, 0, usage.as_mut_ptr()) };
+        if result != -1 {
+            break;
+        }
+
+        let error = io::Error::last_os_error();
+        if error.kind() != io::ErrorKind::Interrupted {
+            return Err(error);
+        }
+    }
+
+    Ok((ExitStatus::from_raw(status), unsafe { usage.assume_init() }))
+}
+
+/// Преобразует [`libc::timeval`] в [`Duration`].
+fn to_duration(time: libc::timeval) -> Duration {
+    Duration::from_secs(time.tv_sec as u64) + Duration::from_micros(time.tv_usec as u64)
+}
+
+/// Отправляет сигнал `sig` процессу `pid`.
+fn signal(pid: libc::pid_t, sig: libc::c_int) {
+    //Процесс мог уже завершиться, ошибка не важна
+    unsafe {
+        libc::kill(pid, sig);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::child::{wait, Exit};
+    use std::os::unix::process::ExitStatusExt;
+    use std::process::{Command, ExitStatus};
+    use std::time::Duration;
+
+    #[test]
+    fn wait_test() {
+        let mut child = Command::new("true").spawn().unwrap();
+        let exit = wait(&mut child, Some(Duration::from_secs(10))).unwrap();
+
+        assert!(exit.status.success());
+        assert!(!exit.timed_out);
+        assert!(exit.rss > 0);
+    }
+
+    #[test]
+    fn exit_test() {
+        let mut usage: libc::rusage = unsafe { std::mem::zeroed() };
+        usage.ru_utime.tv_sec = 1;
+        usage.ru_utime.tv_usec = 500_000;
+        usage.ru_stime.tv_usec = 250;
+        usage.ru_maxrss = 4096;
+        let exit = Exit::new(ExitStatus::from_raw(0), &usage, false);
+
+        assert_eq!(exit.user, Duration::from_millis(1500));
+        assert_eq!(exit.sys, Duration::from_micros(250));
+        //На macOS ru_maxrss в байтах
+        let rss = if cfg!(target_os = "macos") { 4 } else { 4096 };
+        assert_eq!(exit.rss, rss);
+    }
+
+    #[test]
+    fn wait_cpu_test() {
+        let mut child = Command::new("sh")
+            .arg("-c")
+            .arg("i=0; while [ $i -lt 100000 ]; do i=$((i + 1)); done")
+            .spawn()
+            .unwrap();
+        let exit = wait(&mut child, None).unwrap();
+
+        assert!(exit.status.success());
+        assert!(exit.user > Duration::default());
+        assert!(exit.rss > 0);
+    }
+
+    #[test]
+    fn wait_finished_test() {
+        //Процесс завершается раньше таймаута и не считается прерванным
+        for _ in 0..5 {
+            let mut child = Command::new("true").spawn().unwrap();
+            std::thread::sleep(Duration::from_millis(50));
+            let exit = wait(&mut child, Some(Duration::from_millis(1))).unwrap();
+
+            assert!(exit.status.success());
+            assert!(!exit.timed_out);
+        }
+    }
+
+    #[test]
+    fn wait_timeout_test() {
+        let mut child = Command::new("sleep").arg("10").spawn().unwrap();
+        let exit = wait(&mut child, Some(Duration::from_millis(50))).unwrap();
+
+        assert!(!exit.status.success());
+        assert!(exit.timed_out);
+    }
+}
@@ -1,31 +1,140 @@
 //! Вывод асимптотической временной сложности в BigO нотации.
 
-use crate::run::Run;
+use crate::run::{percentile, Run};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use validator::{Validate, ValidationError, ValidationErrors};
 
 /// Варианты временных сложностей.
-#[derive(Copy, Clone, Debug)]
+#[derive(Serialize, Deserialize, Copy, Clone, PartialEq, Debug)]
 pub enum Complexity {
     O1,
     OLogN,
+    OLogSquaredN,
+    OSqrtN,
     ON,
     ONLogN,
     ONSquared,
     ONCubed,
+    /// N^k для произвольного `k` > 0.
+    ONPow(f64),
+    O2N,
+    ONFactorial,
+    /// Произведение переменных с индексами `i` и `j`: O(N·M).
+    ONTimesM(usize, usize),
+    /// Сумма переменных с индексами `i` и `j`: O(N+M).
+    ONPlusM(usize, usize),
+    /// Переменная `i`, умноженная на логарифм переменной `j`: O(N·logM).
+    ONLogM(usize, usize),
+    #[serde(skip)]
     Unknown,
 }
 
+/// Сложности, среди которых по умолчанию выбирается наилучшая.
+/// Сложности от нескольких переменных не подходят, если переменная одна.
+const COMPLEXITIES: [Complexity; 14] = [
+    Complexity::O1,
+    Complexity::OLogN,
+    Complexity::OLogSquaredN,
+    Complexity::OSqrtN,
+    Complexity::ON,
+    Complexity::ONLogN,
+    Complexity::ONSquared,
+    Complexity::ONCubed,
+    Complexity::O2N,
+    Complexity::ONFactorial,
+    Complexity::ONTimesM(0, 1),
+    Complexity::ONPlusM(0, 1),
+    Complexity::ONLogM(0, 1),
+    Complexity::ONLogM(1, 0),
+];
+
+/// Имя суммарной длины аргументов в сложностях от одной переменной, если переменных несколько.
+pub const TOTAL_NAME: &str = "S";
+
+/// Возвращает имя переменной с индексом `index`: N, M, K, L, затем N4, N5...
+pub fn variable_name(index: usize) -> String {
+    const NAMES: [&str; 4] = ["N", "M", "K", "L"];
+
+    match NAMES.get(index) {
+        Some(name) => name.to_string(),
+        None => format!("N{}", index),
+    }
+}
+
 impl Complexity {
     /// Возвращает функции соответсвующей ей сложности. O(N) -> N, O(N^2) -> N^2...
+    /// При переполнении функция возвращает [`f64::INFINITY`].
+    /// Для сложностей от нескольких переменных возвращает [`f64::NAN`], их значение вычисляет [`Complexity::evaluate`].
     pub fn curve(&self) -> impl Fn(usize) -> f64 {
-        match self {
-            Complexity::OLogN => |s| (s as f64).log2(),
-            Complexity::ON => |s| s as f64,
-            Complexity::ONLogN => |s| (s as f64) * (s as f64).log2(),
-            Complexity::ONSquared => |s| (s as f64).powi(2),
-            Complexity::ONCubed => |s| (s as f64).powi(3),
-            _ => |_| 1.0,
+        let complexity = *self;
+
+        move |s| {
+            let n = s as f64;
+            match complexity {
+                Complexity::OLogN => n.log2(),
+                Complexity::OLogSquaredN => n.log2().powi(2),
+                Complexity::OSqrtN => n.sqrt(),
+                Complexity::ON => n,
+                Complexity::ONLogN => n * n.log2(),
+                Complexity::ONSquared => n.powi(2),
+                Complexity::ONCubed => n.powi(3),
+                Complexity::ONPow(k) => n.powf(k),
+                //2^1024 уже не представимо в f64
+                Complexity::O2N if s < 1024 => 2f64.powi(s as i32),
+                Complexity::O2N => f64::INFINITY,
+                //Произведение становится бесконечным после 170!
+                Complexity::ONFactorial => (2..=s)
+                    .try_fold(1.0, |acc: f64, i| {
+                        let acc = acc * i as f64;
+                        if acc.is_finite() {
+                            Some(acc)
+                        } else {
+                            None
+                        }
+                    })
+                    .unwrap_or(f64::INFINITY),
+                Complexity::ONTimesM(..) | Complexity::ONPlusM(..) | Complexity::ONLogM(..) => {
+                    f64::NAN
+                }
+                Complexity::O1 | Complexity::Unknown => 1.0,
+            }
+        }
+    }
+
+    /// Вычисляет значение функции сложности для суммарной длины `len` и размеров по переменным `sizes`.
+    /// Если переменной с нужным индексом нет, возвращает [`f64::NAN`].
+    pub fn evaluate(&self, len: usize, sizes: &[usize]) -> f64 {
+        let size = |index: usize| sizes.get(index).map_or(f64::NAN, |&size| size as f64);
+
+        match *self {
+            Complexity::ONTimesM(i, j) => size(i) * size(j),
+            Complexity::ONPlusM(i, j) => size(i) + size(j),
+            Complexity::ONLogM(i, j) => size(i) * size(j).log2(),
+            _ => self.curve()(len),
+        }
+    }
+
+    /// Возвращает индексы переменных сложности от нескольких переменных.
+    /// Сложности от одной переменной зависят от суммарной длины аргументов и возвращают `None`.
+    pub fn variables(&self) -> Option<(usize, usize)> {
+        match *self {
+            Complexity::ONTimesM(i, j) | Complexity::ONPlusM(i, j) | Complexity::ONLogM(i, j) => {
+                Some((i, j))
+            }
+            _ => None,
+        }
+    }
+
+    /// Запись сложности, в которой суммарная длина аргументов названа `total`.
+    /// Например, O(NlogN) -> O(SlogS). Сложности от нескольких переменных не меняются.
+    pub fn name_with(&self, total: &str) -> String {
+        match self.variables() {
+            Some(_) => self.to_string(),
+            None => self.to_string().replace('N', total),
         }
     }
 }
@@ -35,10 +144,24 @@ impl Display for Complexity {
         let complexity_str = match self {
             Complexity::O1 => "O(1)",
             Complexity::OLogN => "O(logN)",
+            Complexity::OLogSquaredN => "O(log^2N)",
+            Complexity::OSqrtN => "O(sqrtN)",
             Complexity::ON => "O(N)",
             Complexity::ONLogN => "O(NlogN)",
             Complexity::ONSquared => "O(N^2)",
             Complexity::ONCubed => "O(N^3)",
+            Complexity::ONPow(k) => return write!(f, "O(N^{})", k),
+            Complexity::O2N => "O(2^N)",
+            Complexity::ONFactorial => "O(N!)",
+            Complexity::ONTimesM(i, j) => {
+                return write!(f, "O({}·{})", variable_name(*i), variable_name(*j))
+            }
+            Complexity::ONPlusM(i, j) => {
+                return write!(f, "O({}+{})", variable_name(*i), variable_name(*j))
+            }
+            Complexity::ONLogM(i, j) => {
+                return write!(f, "O({}·log{})", variable_name(*i), variable_name(*j))
+            }
             Complexity::Unknown => "Unknown",
         };
 
@@ -46,67 +169,1218 @@ impl Display for Complexity {
     }
 }
 
+impl Validate for Complexity {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        match self {
+            Complexity::ONPow(k) if !(k.is_finite() && *k > 0.0) => {
+                let mut errors = ValidationErrors::new();
+                let mut error = ValidationError::new("k <= 0");
+                error.add_param("k".into(), k);
+                errors.add("Complexity::ONPow", error);
+
+                Err(errors)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Вид аппроксимирующей функции.
+#[derive(Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum FitMode {
+    /// `coef * f(N)`. Функция проходит через начало координат.
+    Origin,
+    /// `coef * f(N) + offset`. Смещение поглощает постоянные затраты, например, на запуск процесса.
+    Affine,
+}
+
+impl Default for FitMode {
+    fn default() -> Self {
+        FitMode::Origin
+    }
+}
+
+/// Метод оценки коэффициентов аппроксимирующей функции.
+#[derive(Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum Estimator {
+    /// Метод наименьших квадратов.
+    LeastSquares,
+    /// Оценка Тейла-Сена: медиана наклонов по всем парам точек.
+    TheilSen,
+    /// М-оценка Хьюбера: наименьшие квадраты с уменьшенным весом точек с большими остатками.
+    Huber,
+}
+
+impl Default for Estimator {
+    fn default() -> Self {
+        Estimator::LeastSquares
+    }
+}
+
+/// Веса поколений при аппроксимации.
+#[derive(Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum Weighting {
+    /// Все поколения имеют одинаковый вес.
+    Uniform,
+    /// Вес обратно пропорционален дисперсии замеров поколения.
+    InverseVariance,
+    /// Вес обратно пропорционален квадрату значения: минимизируется относительная ошибка.
+    Relative,
+}
+
+impl Default for Weighting {
+    fn default() -> Self {
+        Weighting::Uniform
+    }
+}
+
+/// Критерий выбора наилучшей сложности.
+#[derive(Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum Selection {
+    /// Наименьшая ошибка.
+    Rms,
+    /// Информационный критерий Акаике: `n * ln(RSS / n) + 2k`.
+    Aic,
+    /// Байесовский информационный критерий: `n * ln(RSS / n) + k * ln(n)`.
+    Bic,
+}
+
+impl Default for Selection {
+    fn default() -> Self {
+        Selection::Rms
+    }
+}
+
+impl Selection {
+    /// Штраф за один параметр модели при `n` точках. Для [`Selection::Rms`] равен 0.
+    fn penalty(self, n: usize) -> f64 {
+        match self {
+            Selection::Rms => 0.0,
+            Selection::Aic => 2.0,
+            Selection::Bic => (n as f64).ln(),
+        }
+    }
+}
+
+impl Display for Selection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let selection_str = match self {
+            Selection::Rms => "RMS",
+            Selection::Aic => "AIC",
+            Selection::Bic => "BIC",
+        };
+
+        write!(f, "{}", selection_str)
+    }
+}
+
+/// Настройки вычисления асимптотической сложности.
+#[derive(Deserialize, Validate, Clone)]
+pub struct FitConfig {
+    /// Вид аппроксимирующей функции. По умолчанию [`FitMode::Origin`].
+    #[serde(default)]
+    pub fit_mode: FitMode,
+    /// Метод оценки коэффициентов. По умолчанию [`Estimator::LeastSquares`].
+    #[serde(default)]
+    pub estimator: Estimator,
+    /// Веса поколений. По умолчанию [`Weighting::Uniform`]. Не используется [`Estimator::TheilSen`].
+    #[serde(default)]
+    pub weighting: Weighting,
+    /// Критерий выбора сложности. По умолчанию [`Selection::Rms`].
+    #[serde(default)]
+    pub selection: Selection,
+    /// Сложности, среди которых выбирается наилучшая. По умолчанию [`COMPLEXITIES`].
+    #[serde(default = "FitConfig::complexities_default")]
+    #[validate(custom = "FitConfig::validate_complexities")]
+    #[validate]
+    pub complexities: Vec<Complexity>,
+    /// Относительная разница ошибок двух лучших сложностей, при которой результат считается неоднозначным.
+    /// По умолчанию 0.05.
+    #[serde(default = "FitConfig::ambiguity_margin_default")]
+    #[validate(range(min = 0.0))]
+    pub ambiguity_margin: f64,
+    /// Количество повторных выборок бутстрепа. По умолчанию 0 - бутстреп не выполняется.
+    #[serde(default)]
+    pub bootstrap: usize,
+    /// Уровень доверия интервалов бутстрепа. По умолчанию 0.95.
+    #[serde(default = "FitConfig::confidence_level_default")]
+    #[validate(range(min = 0.0, max = 1.0))]
+    pub confidence_level: f64,
+}
+
+impl FitConfig {
+    /// Возвращает значение `complexities` по умолчанию - [`COMPLEXITIES`]. Используется [`serde`].
+    fn complexities_default() -> Vec<Complexity> {
+        COMPLEXITIES.to_vec()
+    }
+
+    /// Возвращает значение `ambiguity_margin` по умолчанию - 0.05. Используется [`serde`].
+    const fn ambiguity_margin_default() -> f64 {
+        0.05
+    }
+
+    /// Возвращает значение `confidence_level` по умолчанию - 0.95. Используется [`serde`].
+    const fn confidence_level_default() -> f64 {
+        0.95
+    }
+
+    /// Проверяет, что `complexities` не пустой.
+    fn validate_complexities(complexities: &[Complexity]) -> Result<(), ValidationError> {
+        if complexities.is_empty() {
+            Err(ValidationError::new("complexities is empty"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+impl Default for FitConfig {
+    fn default() -> Self {
+        Self {
+            fit_mode: FitMode::default(),
+            estimator: Estimator::default(),
+            weighting: Weighting::default(),
+            selection: Selection::default(),
+            complexities: Self::complexities_default(),
+            ambiguity_margin: Self::ambiguity_margin_default(),
+            bootstrap: 0,
+            confidence_level: Self::confidence_level_default(),
+        }
+    }
+}
+
+/// Эмпирический порядок роста `N^exponent`, полученный линейной регрессией `log(time)` от `log(N)`.
+#[derive(Copy, Clone, Debug)]
+pub struct PowerLaw {
+    /// Показатель степени.
+    pub exponent: f64,
+    /// Коэффициент детерминации регрессии.
+    pub r_squared: f64,
+}
+
+impl PowerLaw {
+    /// Оценивает показатель степени по точкам `(N, time)`. Точки с неположительным временем не учитываются.
+    /// Возвращает `None`, если различных `N` меньше двух.
+    pub fn estimate(points: &[(usize, f64)]) -> Option<Self> {
+        let logs: Vec<(f64, f64)> = points
+            .iter()
+            .filter(|&&(size, time)| size > 0 && time > 0.0)
+            .map(|&(size, time)| ((size as f64).ln(), time.ln()))
+            .collect();
+
+        let len = logs.len() as f64;
+        let mean_x = logs.iter().map(|p| p.0).sum::<f64>() / len;
+        let mean_y = logs.iter().map(|p| p.1).sum::<f64>() / len;
+
+        let (sxx, sxy, syy) = logs.iter().fold((0.0, 0.0, 0.0), |acc, &(x, y)| {
+            let (dx, dy) = (x - mean_x, y - mean_y);
+            (acc.0 + dx * dx, acc.1 + dx * dy, acc.2 + dy * dy)
+        });
+
+        if logs.len() < 2 || sxx <= 0.0 {
+            return None;
+        }
+
+        let exponent = sxy / sxx;
+        //Если время не меняется, регрессия объясняет его полностью
+        let r_squared = if syy > 0.0 {
+            (sxy * sxy) / (sxx * syy)
+        } else {
+            1.0
+        };
+
+        Some(PowerLaw {
+            exponent,
+            r_squared,
+        })
+    }
+}
+
+impl Display for PowerLaw {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "N^{:.2} (R²={:.3})", self.exponent, self.r_squared)
+    }
+}
+
+/// Точка аппроксимации: поколение и его значение.
+#[derive(Clone, Debug)]
+struct Point {
+    /// Суммарная длина аргументов.
+    len: usize,
+    /// Размеры по переменным.
+    sizes: Vec<usize>,
+    /// Значение поколения.
+    value: f64,
+}
+
+/// Результат аппроксимации одной из сложностей.
+#[derive(Copy, Clone, Debug)]
+pub struct Candidate {
+    /// Сложность.
+    pub complexity: Complexity,
+    /// Коэффициент.
+    pub coef: f64,
+    /// Смещение. Равно 0 при [`FitMode::Origin`].
+    pub offset: f64,
+    /// Ошибка.
+    pub rms: f64,
+    /// Относительное правдоподобие среди всех кандидатов. В сумме дают 1.
+    pub confidence: f64,
+    /// Значение информационного критерия, если сложность выбиралась по нему.
+    pub ic: Option<f64>,
+}
+
+impl Candidate {
+    /// Сложность, функция которой переполнилась. Ошибка равна [`f64::INFINITY`].
+    fn overflowed(complexity: Complexity) -> Self {
+        Candidate {
+            complexity,
+            coef: 0.0,
+            offset: 0.0,
+            rms: f64::INFINITY,
+            confidence: 0.0,
+            ic: None,
+        }
+    }
+
+    /// Вычисляет `confidence` кандидатов, отсортированных по возрастанию `rms`.
+    /// При нормальных ошибках правдоподобие пропорционально `rms^-n`, где `n` - количество точек.
+    /// Если вычислен информационный критерий, кандидаты отсортированы по нему,
+    /// а правдоподобие пропорционально `exp(-ic / 2)`.
+    fn assign_confidence(candidates: &mut [Candidate], n: usize) {
+        let best = match candidates.first() {
+            Some(best) => *best,
+            None => return,
+        };
+
+        for candidate in candidates.iter_mut() {
+            candidate.confidence = match (candidate.ic, best.ic) {
+                (Some(ic), Some(best)) => (-(ic - best) / 2.0).exp(),
+                _ => {
+                    let ratio = if candidate.rms == best.rms {
+                        1.0
+                    } else {
+                        candidate.rms / best.rms
+                    };
+                    (-(n as f64) * ratio.ln()).exp()
+                }
+            };
+        }
+
+        let total: f64 = candidates.iter().map(|c| c.confidence).sum();
+        for candidate in candidates.iter_mut() {
+            candidate.confidence /= total;
+        }
+    }
+}
+
+#[derive(Debug)]
 pub struct LeastSquares {
     /// Коэффициент
     pub coef: f64,
+    /// Смещение. Равно 0 при [`FitMode::Origin`].
+    pub offset: f64,
     /// Сложность программы.
     pub complexity: Complexity,
     /// Ошибка.
     pub rms: f64,
+    /// Эмпирический порядок роста.
+    pub power_law: Option<PowerLaw>,
+    /// Все подошедшие сложности по возрастанию ошибки. Первая совпадает с итоговой.
+    pub candidates: Vec<Candidate>,
+    /// Вторая по точности сложность, если её ошибка отличается от итоговой не более чем на `ambiguity_margin`.
+    pub ambiguous: Option<Complexity>,
+    /// Результат бутстрепа, если он был запрошен.
+    pub bootstrap: Option<Bootstrap>,
+    /// Критерий, по которому выбрана сложность.
+    pub selection: Selection,
 }
 
 impl LeastSquares {
+    /// Неизвестная сложность с бесконечной ошибкой.
+    #[doc(hidden)]
+    fn unknown() -> Self {
+        LeastSquares {
+            coef: 0.0,
+            offset: 0.0,
+            complexity: Complexity::Unknown,
+            rms: f64::INFINITY,
+            power_law: None,
+            candidates: Vec::new(),
+            ambiguous: None,
+            bootstrap: None,
+            selection: Selection::default(),
+        }
+    }
+
+    /// Аппроксимирует точки `points` с весами `weights` функцией сложности `complexity` методом `config.estimator`.
+    /// Если функция переполняется, ошибка равна [`f64::INFINITY`].
+    #[doc(hidden)]
+    fn candidate(
+        points: &[Point],
+        weights: &[f64],
+        config: &FitConfig,
+        complexity: Complexity,
+    ) -> Candidate {
+        match config.estimator {
+            Estimator::LeastSquares => {
+                Self::minimal_least_squares(points, weights, config.fit_mode, complexity)
+            }
+            Estimator::TheilSen | Estimator::Huber => Self::robust(
+                points,
+                weights,
+                config.fit_mode,
+                config.estimator,
+                complexity,
+            ),
+        }
+    }
+
+    /// Аппроксимирует точки `points` с весами `weights` функцией сложности `complexity`.
+    /// Ошибка - взвешенное среднеквадратичное отклонение, отнесенное к взвешенному среднему значению.
+    /// Если функция переполняется, ошибка равна [`f64::INFINITY`].
+    #[doc(hidden)]
+    fn minimal_least_squares(
+        points: &[Point],
+        weights: &[f64],
+        mode: FitMode,
+        complexity: Complexity,
+    ) -> Candidate {
+        let curve = match Self::curve_points(points, complexity) {
+            Some(curve) => curve,
+            None => return Candidate::overflowed(complexity),
+        };
+        let (coef, offset) = Self::weighted_least_squares(&curve, weights, mode);
+
+        let total: f64 = weights.iter().sum();
+        let (times, rms) =
+            curve
+                .iter()
+                .zip(weights)
+                .fold((0.0, 0.0), |acc, (&(point, time), &weight)| {
+                    let fit = coef * point + offset;
+                    (acc.0 + weight * time, acc.1 + weight * (time - fit).powi(2))
+                });
+
+        Candidate {
+            complexity,
+            coef,
+            offset,
+            rms: (rms / total).sqrt() / (times / total),
+            confidence: 0.0,
+            ic: None,
+        }
+    }
+
+    /// Аппроксимирует точки `points` функцией сложности `complexity` устойчивым к выбросам методом `estimator`.
+    /// Ошибкой считается устойчивая оценка разброса остатков `1.4826 * median(|r|)`, отнесенная к среднему значению.
+    #[doc(hidden)]
+    fn robust(
+        points: &[Point],
+        weights: &[f64],
+        mode: FitMode,
+        estimator: Estimator,
+        complexity: Complexity,
+    ) -> Candidate {
+        let curve = match Self::curve_points(points, complexity) {
+            Some(curve) => curve,
+            None => return Candidate::overflowed(complexity),
+        };
+        let (coef, offset) = match estimator {
+            Estimator::Huber => Self::huber(&curve, weights, mode),
+            _ => Self::theil_sen(&curve, mode),
+        };
+
+        let len = points.len() as f64;
+        let times: f64 = curve.iter().map(|&(_, time)| time).sum();
+        let residuals: Vec<f64> = curve
+            .iter()
+            .map(|&(point, time)| time - coef * point - offset)
+            .collect();
+
+        Candidate {
+            complexity,
+            coef,
+            offset,
+            rms: robust_scale(&residuals) / (times / len),
+            confidence: 0.0,
+            ic: None,
+        }
+    }
+
+    /// Возвращает пары `(f(N), value)` для точек `points`, или `None`, если функция сложности переполнилась
+    /// или не определена.
+    #[doc(hidden)]
+    fn curve_points(points: &[Point], complexity: Complexity) -> Option<Vec<(f64, f64)>> {
+        let curve: Vec<(f64, f64)> = points
+            .iter()
+            .map(|point| (complexity.evaluate(point.len, &point.sizes), point.value))
+            .collect();
+
+        //Функция переполнилась хотя бы в одной точке, такая сложность не подходит
+        if curve.iter().any(|&(point, _)| !point.powi(2).is_finite()) {
+            None
+        } else {
+            Some(curve)
+        }
+    }
+
+    /// Взвешенный метод наименьших квадратов для пар `(f(N), value)` с весами `weights`.
+    /// Возвращает коэффициент и смещение.
+    #[doc(hidden)]
+    fn weighted_least_squares(curve: &[(f64, f64)], weights: &[f64], mode: FitMode) -> (f64, f64) {
+        let (sum, point, sq_point, times, time_point) = curve.iter().zip(weights).fold(
+            (0.0, 0.0, 0.0, 0.0, 0.0),
+            |acc, (&(point, time), &weight)| {
+                (
+                    acc.0 + weight,
+                    acc.1 + weight * point,
+                    acc.2 + weight * point.powi(2),
+                    acc.3 + weight * time,
+                    acc.4 + weight * time * point,
+                )
+            },
+        );
+
+        //Если все точки функции совпадают (например, O(1)), смещение неотличимо от коэффициента
+        let det = sum * sq_point - point.powi(2);
+        match mode {
+            FitMode::Affine if det.abs() > f64::EPSILON * sq_point * sum => {
+                let coef = (sum * time_point - point * times) / det;
+                (coef, (times - coef * point) / sum)
+            }
+            _ => (time_point / sq_point, 0.0),
+        }
+    }
+
+    /// Оценка Тейла-Сена. При [`FitMode::Origin`] коэффициент - медиана отношений `value / f(N)`,
+    /// при [`FitMode::Affine`] - медиана наклонов по всем парам точек, смещение - медиана остатков.
     #[doc(hidden)]
-    fn minimal_least_squares<F>(runs: &[Run], fitting_curve: F) -> Self
+    fn theil_sen(curve: &[(f64, f64)], mode: FitMode) -> (f64, f64) {
+        let mut slopes = Vec::new();
+        if mode == FitMode::Affine {
+            for (i, &(x1, y1)) in curve.iter().enumerate() {
+                for &(x2, y2) in &curve[i + 1..] {
+                    if (x2 - x1).abs() > f64::EPSILON * x1.abs().max(x2.abs()) {
+                        slopes.push((y2 - y1) / (x2 - x1));
+                    }
+                }
+            }
+        }
+
+        //Если все точки функции совпадают, смещение неотличимо от коэффициента
+        if slopes.is_empty() {
+            let ratios: Vec<f64> = curve
+                .iter()
+                .filter(|&&(point, _)| point > 0.0)
+                .map(|&(point, time)| time / point)
+                .collect();
+
+            return (median(ratios), 0.0);
+        }
+
+        let coef = median(slopes);
+        let offset = median(
+            curve
+                .iter()
+                .map(|&(point, time)| time - coef * point)
+                .collect(),
+        );
+
+        (coef, offset)
+    }
+
+    /// М-оценка Хьюбера итеративно перевзвешенным методом наименьших квадратов.
+    /// Точки с остатком больше `1.345` устойчивых отклонений получают вес, обратно пропорциональный остатку.
+    /// Итоговый вес точки - произведение исходного `base` и веса Хьюбера.
+    #[doc(hidden)]
+    fn huber(curve: &[(f64, f64)], base: &[f64], mode: FitMode) -> (f64, f64) {
+        const TUNING: f64 = 1.345;
+        const ITERATIONS: usize = 50;
+
+        let mut weights = base.to_vec();
+        let (mut coef, mut offset) = Self::weighted_least_squares(curve, &weights, mode);
+
+        for _ in 0..ITERATIONS {
+            let residuals: Vec<f64> = curve
+                .iter()
+                .map(|&(point, time)| time - coef * point - offset)
+                .collect();
+            let threshold = TUNING * robust_scale(&residuals);
+            if threshold <= 0.0 {
+                break;
+            }
+
+            for ((weight, base), residual) in weights.iter_mut().zip(base).zip(&residuals) {
+                *weight = if residual.abs() <= threshold {
+                    *base
+                } else {
+                    base * threshold / residual.abs()
+                };
+            }
+
+            let (next_coef, next_offset) = Self::weighted_least_squares(curve, &weights, mode);
+            let converged = (next_coef - coef).abs() <= 1e-12 * coef.abs()
+                && (next_offset - offset).abs() <= 1e-12 * offset.abs().max(1.0);
+            coef = next_coef;
+            offset = next_offset;
+
+            if converged {
+                break;
+            }
+        }
+
+        (coef, offset)
+    }
+
+    /// Вычисляет асимптотическую сложность методом наименьших квадратов, а также эмпирический порядок роста [`PowerLaw`].
+    /// Значение поколения (время выполнения, память) возвращает `value`, стандартное отклонение замеров поколения - `spread`.
+    /// Поколения, прерванные по ограничению времени, не учитываются.
+    /// Если задан `bootstrap`, дополнительно вычисляется [`Bootstrap`].
+    pub fn computate_big_o<V, S>(times: &[Run], value: V, spread: S, config: &FitConfig) -> Self
     where
-        F: Fn(usize) -> f64,
+        V: Fn(&Run) -> f64,
+        S: Fn(&Run) -> f64,
     {
-        let (sq_points, times, time_points) = runs.iter().fold((0.0, 0.0, 0.0), |acc, run| {
-            let point = fitting_curve(run.len);
+        let points = Self::points(times, &value);
+        let weights = Self::weights(times, &points, &spread, config.weighting);
+        let mut fit = Self::fit(&points, &weights, config);
 
-            let sq_points = acc.0 + point.powi(2);
-            let times = acc.1 + run.min;
-            let time_points = acc.2 + run.min * point;
+        if config.bootstrap > 0 && !fit.candidates.is_empty() {
+            fit.bootstrap = Some(Bootstrap::estimate(
+                times,
+                &value,
+                &spread,
+                config,
+                fit.complexity,
+                &mut rand::thread_rng(),
+            ));
+        }
 
-            (sq_points, times, time_points)
-        });
+        fit
+    }
 
-        let coef = time_points / sq_points;
-        let rms = runs.iter().fold(0.0, |acc, run| {
-            let fit = coef * fitting_curve(run.len);
-            acc + (run.min - fit).powi(2)
-        });
+    /// Точки завершившихся поколений.
+    #[doc(hidden)]
+    fn points<V>(times: &[Run], value: &V) -> Vec<Point>
+    where
+        V: Fn(&Run) -> f64,
+    {
+        times
+            .iter()
+            .filter(|run| !run.timed_out)
+            .map(|run| Point {
+                len: run.len,
+                sizes: run.sizes.clone(),
+                value: value(run),
+            })
+            .collect()
+    }
+
+    /// Веса точек `points` завершившихся поколений `times` в соответствии с `weighting`.
+    /// Если вес хотя бы одной точки не определен (например, разброс поколения с одной итерацией равен 0),
+    /// все точки получают одинаковый вес.
+    #[doc(hidden)]
+    fn weights<S>(times: &[Run], points: &[Point], spread: &S, weighting: Weighting) -> Vec<f64>
+    where
+        S: Fn(&Run) -> f64,
+    {
+        let weights: Vec<f64> = match weighting {
+            Weighting::Uniform => return vec![1.0; points.len()],
+            Weighting::InverseVariance => times
+                .iter()
+                .filter(|run| !run.timed_out)
+                .map(|run| spread(run).powi(-2))
+                .collect(),
+            Weighting::Relative => points.iter().map(|point| point.value.powi(-2)).collect(),
+        };
+
+        if weights
+            .iter()
+            .all(|weight| weight.is_finite() && *weight > 0.0)
+        {
+            weights
+        } else {
+            vec![1.0; points.len()]
+        }
+    }
+
+    /// Выбирает наилучшую сложность для точек `points` с весами `weights`.
+    #[doc(hidden)]
+    fn fit(points: &[Point], weights: &[f64], config: &FitConfig) -> Self {
+        let candidates: Vec<Candidate> = config
+            .complexities
+            .iter()
+            .filter(|complexity| Self::identifiable(points, **complexity))
+            .map(|complexity| Self::candidate(points, weights, config, *complexity))
+            .filter(|candidate| candidate.rms.is_finite())
+            .collect();
+        let mut candidates = Self::distinct(points, config.fit_mode, candidates);
+        if config.selection == Selection::Rms {
+            candidates.sort_by(|a, b| a.rms.partial_cmp(&b.rms).unwrap());
+        } else {
+            for candidate in candidates.iter_mut() {
+                let params = Self::params(points, config.fit_mode, candidate.complexity);
+                candidate.ic = Some(Self::information_criterion(
+                    candidate.rms,
+                    params,
+                    points.len(),
+                    config.selection,
+                ));
+            }
+            candidates.sort_by(|a, b| a.ic.partial_cmp(&b.ic).unwrap());
+        }
+        Candidate::assign_confidence(&mut candidates, points.len());
+
+        let best = match candidates.first() {
+            Some(best) => *best,
+            None => return Self::unknown(),
+        };
+        let lens: Vec<(usize, f64)> = points
+            .iter()
+            .map(|point| (point.len, point.value))
+            .collect();
+        //По информационному критерию модели неразличимы, если критерии отличаются меньше чем на 2
+        let ambiguous = candidates
+            .get(1)
+            .filter(|second| match (second.ic, best.ic) {
+                (Some(second), Some(best)) => second - best < 2.0,
+                _ => second.rms - best.rms <= config.ambiguity_margin * best.rms,
+            })
+            .map(|second| second.complexity);
 
-        let len = runs.len() as f64;
         LeastSquares {
-            coef,
-            complexity: Complexity::Unknown,
-            rms: (rms / len).sqrt() / (times / len),
+            coef: best.coef,
+            offset: best.offset,
+            complexity: best.complexity,
+            rms: best.rms,
+            power_law: PowerLaw::estimate(&lens),
+            candidates,
+            ambiguous,
+            bootstrap: None,
+            selection: config.selection,
         }
     }
 
-    /// Вычисляет временную сложность на основе времени выполнения программы методов наименьших квадратов.
-    pub fn computate_big_o(times: &[Run]) -> Self {
-        const COMPLEXITIES: [Complexity; 5] = [
-            Complexity::OLogN,
-            Complexity::ON,
-            Complexity::ONLogN,
-            Complexity::ONSquared,
-            Complexity::ONCubed,
-        ];
+    /// Проверяет, что сложность от нескольких переменных различима на точках `points`:
+    /// отношение её переменных меняется между поколениями. Если переменные растут пропорционально
+    /// (например, все диапазоны с одинаковым multiplier), то O(N·M) неотличимо от O(S^2), а O(N+M) - от O(S).
+    #[doc(hidden)]
+    fn identifiable(points: &[Point], complexity: Complexity) -> bool {
+        let (i, j) = match complexity.variables() {
+            Some((i, j)) if i != j => (i, j),
+            _ => return true,
+        };
+        let ratio = |point: &Point| match (point.sizes.get(i), point.sizes.get(j)) {
+            (Some(&n), Some(&m)) => Some((n as u128, m as u128)),
+            _ => None,
+        };
 
-        let mut best_fit = Self::minimal_least_squares(times, Complexity::O1.curve());
-        best_fit.complexity = Complexity::O1;
+        let first = match points.first().and_then(ratio) {
+            Some(first) => first,
+            None => return true,
+        };
+        points
+            .iter()
+            .filter_map(ratio)
+            .any(|(n, m)| n * first.1 != m * first.0)
+    }
 
-        for complexity in &COMPLEXITIES {
-            let current_fit = Self::minimal_least_squares(times, complexity.curve());
+    /// Оставляет по одному кандидату из сложностей, функции которых совпадают на точках `points`
+    /// с точностью до коэффициента (при [`FitMode::Affine`] - и до смещения): их аппроксимации одинаковы.
+    /// Предпочитается сложность от нескольких переменных, например, O(N+M) вместо O(S) от суммарной длины
+    /// двух массивов. Из одинаковых по числу переменных остается первая в `complexities`.
+    #[doc(hidden)]
+    fn distinct(points: &[Point], mode: FitMode, candidates: Vec<Candidate>) -> Vec<Candidate> {
+        let curve = |complexity: Complexity| -> Vec<f64> {
+            let values: Vec<f64> = points
+                .iter()
+                .map(|point| complexity.evaluate(point.len, &point.sizes))
+                .collect();
+            match mode {
+                FitMode::Origin => values,
+                FitMode::Affine => {
+                    let mean = values.iter().sum::<f64>() / values.len() as f64;
+                    values.iter().map(|value| value - mean).collect()
+                }
+            }
+        };
+        //a = c * b, c > 0, если a_i * sum|b| = b_i * sum|a| для всех точек
+        let same = |a: &[f64], b: &[f64]| {
+            let sum_a: f64 = a.iter().map(|x| x.abs()).sum();
+            let sum_b: f64 = b.iter().map(|x| x.abs()).sum();
+            sum_a > 0.0
+                && sum_b > 0.0
+                && a.iter().zip(b).all(|(x, y)| {
+                    (x * sum_b - y * sum_a).abs() <= 1e-9 * (x.abs() * sum_b + y.abs() * sum_a)
+                })
+        };
 
-            if current_fit.rms < best_fit.rms {
-                best_fit = current_fit;
-                best_fit.complexity = *complexity;
+        let mut kept: Vec<(Candidate, Vec<f64>)> = Vec::with_capacity(candidates.len());
+        for candidate in candidates {
+            let values = curve(candidate.complexity);
+            match kept.iter_mut().find(|(_, other)| same(&values, other)) {
+                Some(existing) => {
+                    if candidate.complexity.variables().is_some()
+                        && existing.0.complexity.variables().is_none()
+                    {
+                        *existing = (candidate, values);
+                    }
+                }
+                None => kept.push((candidate, values)),
             }
         }
 
-        best_fit
+        kept.into_iter().map(|(candidate, _)| candidate).collect()
+    }
+
+    /// Количество параметров аппроксимирующей функции сложности `complexity` для точек `points`.
+    /// Смещение не считается параметром, если все точки функции совпадают.
+    #[doc(hidden)]
+    fn params(points: &[Point], mode: FitMode, complexity: Complexity) -> usize {
+        let curve = |point: &Point| complexity.evaluate(point.len, &point.sizes);
+        let constant = points
+            .windows(2)
+            .all(|pair| curve(&pair[0]) == curve(&pair[1]));
+
+        match mode {
+            FitMode::Affine if !constant => 2,
+            _ => 1,
+        }
+    }
+
+    /// Информационный критерий `selection` для `n` точек, модели с `params` параметрами и ошибкой `rms`.
+    /// Ошибка нормирована на среднее значение, поэтому критерий отличается от обычного на одинаковую
+    /// для всех сложностей величину.
+    #[doc(hidden)]
+    fn information_criterion(rms: f64, params: usize, n: usize, selection: Selection) -> f64 {
+        //Точное совпадение дало бы -inf
+        let rms = rms.max(f64::MIN_POSITIVE);
+
+        n as f64 * rms.powi(2).ln() + params as f64 * selection.penalty(n)
+    }
+}
+
+/// Медиана значений `values`.
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    percentile(&values, 0.5)
+}
+
+/// Устойчивая оценка стандартного отклонения остатков `residuals`: `1.4826 * median(|r|)`.
+fn robust_scale(residuals: &[f64]) -> f64 {
+    1.4826 * median(residuals.iter().map(|r| r.abs()).collect())
+}
+
+/// Результат бутстрепа: повторного вычисления сложности по поколениям,
+/// замеры которых случайно выбраны с повторениями из исходных.
+#[derive(Debug)]
+pub struct Bootstrap {
+    /// Количество повторных выборок.
+    pub resamples: usize,
+    /// Уровень доверия интервала `coef_interval`.
+    pub confidence_level: f64,
+    /// Доверительный интервал коэффициента итоговой сложности.
+    pub coef_interval: (f64, f64),
+    /// Сколько раз каждая сложность оказалась лучшей. По убыванию.
+    pub wins: Vec<(Complexity, usize)>,
+}
+
+impl Bootstrap {
+    /// Выполняет `config.bootstrap` повторных выборок.
+    /// `complexity` - итоговая сложность по исходным замерам, для неё строится интервал коэффициента.
+    fn estimate<V, S, R>(
+        times: &[Run],
+        value: &V,
+        spread: &S,
+        config: &FitConfig,
+        complexity: Complexity,
+        rng: &mut R,
+    ) -> Self
+    where
+        V: Fn(&Run) -> f64,
+        S: Fn(&Run) -> f64,
+        R: Rng,
+    {
+        let mut coefs = Vec::with_capacity(config.bootstrap);
+        let mut wins: Vec<(Complexity, usize)> = Vec::new();
+
+        for _ in 0..config.bootstrap {
+            let resampled: Vec<Run> = times.iter().map(|run| run.resample(rng)).collect();
+            let points = LeastSquares::points(&resampled, value);
+            let weights = LeastSquares::weights(&resampled, &points, spread, config.weighting);
+            let fit = LeastSquares::fit(&points, &weights, config);
+
+            if let Some(candidate) = fit.candidates.iter().find(|c| c.complexity == complexity) {
+                coefs.push(candidate.coef);
+            }
+
+            match wins.iter_mut().find(|(c, _)| *c == fit.complexity) {
+                Some((_, count)) => *count += 1,
+                None => wins.push((fit.complexity, 1)),
+            }
+        }
+
+        coefs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        wins.sort_by_key(|&(_, count)| Reverse(count));
+
+        let alpha = 1.0 - config.confidence_level;
+        Bootstrap {
+            resamples: config.bootstrap,
+            confidence_level: config.confidence_level,
+            coef_interval: (
+                percentile(&coefs, alpha / 2.0),
+                percentile(&coefs, 1.0 - alpha / 2.0),
+            ),
+            wins,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::complexity::{
+        Complexity, Estimator, FitConfig, FitMode, LeastSquares, PowerLaw, Selection, Weighting,
+    };
+    use crate::run::{Metric, Run, Sample, Statistic};
+
+    fn runs<F: Fn(f64) -> f64>(time: F) -> Vec<Run> {
+        runs_with_lens((0..8).map(|i| 1000 << i), time)
+    }
+
+    fn runs_with_lens<I, F>(lens: I, time: F) -> Vec<Run>
+    where
+        I: Iterator<Item = usize>,
+        F: Fn(f64) -> f64,
+    {
+        lens.map(|len| Run {
+            min: time(len as f64),
+            len,
+            ..Run::default()
+        })
+        .collect()
+    }
+
+    #[test]
+    fn origin_fit_test() {
+        let runs = runs(|n| 3.0 * n * n);
+        let fit =
+            LeastSquares::computate_big_o(&runs, |run| run.min, |_| 0.0, &FitConfig::default());
+
+        assert!(matches!(fit.complexity, Complexity::ONSquared));
+        assert!((fit.coef - 3.0).abs() < 1e-9);
+        assert_eq!(fit.offset, 0.0);
+    }
+
+    #[test]
+    fn affine_fit_test() {
+        let runs = runs(|n| 2.0 * n + 50_000.0);
+        let config = FitConfig {
+            fit_mode: FitMode::Affine,
+            ..FitConfig::default()
+        };
+        let fit = LeastSquares::computate_big_o(&runs, |run| run.min, |_| 0.0, &config);
+
+        assert!(matches!(fit.complexity, Complexity::ON));
+        assert!((fit.coef - 2.0).abs() < 1e-6);
+        assert!((fit.offset - 50_000.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn exponential_fit_test() {
+        let runs = runs_with_lens(10..20, |n| 0.001 * 2f64.powf(n));
+        let fit =
+            LeastSquares::computate_big_o(&runs, |run| run.min, |_| 0.0, &FitConfig::default());
+
+        assert_eq!(fit.complexity, Complexity::O2N);
+    }
+
+    #[test]
+    fn pow_fit_test() {
+        let runs = runs(|n| n.powf(1.5));
+        let config = FitConfig {
+            complexities: vec![
+                Complexity::ON,
+                Complexity::ONPow(1.5),
+                Complexity::ONSquared,
+            ],
+            ..FitConfig::default()
+        };
+        let fit = LeastSquares::computate_big_o(&runs, |run| run.min, |_| 0.0, &config);
+
+        assert_eq!(fit.complexity, Complexity::ONPow(1.5));
+        assert_eq!(fit.complexity.to_string(), "O(N^1.5)");
+    }
+
+    #[test]
+    fn overflow_curve_test() {
+        assert_eq!(Complexity::ONFactorial.curve()(5), 120.0);
+        assert_eq!(Complexity::ONFactorial.curve()(200), f64::INFINITY);
+        assert_eq!(Complexity::O2N.curve()(4096), f64::INFINITY);
+    }
+
+    #[test]
+    fn power_law_test() {
+        let points: Vec<(usize, f64)> = (1..10)
+            .map(|i| (i * 100, 5.0 * (i as f64).powf(1.93)))
+            .collect();
+        let power_law = PowerLaw::estimate(&points).unwrap();
+
+        assert!((power_law.exponent - 1.93).abs() < 1e-9);
+        assert!((power_law.r_squared - 1.0).abs() < 1e-9);
+        assert!(PowerLaw::estimate(&points[..1]).is_none());
+    }
+
+    #[test]
+    fn ambiguity_test() {
+        //Шум, чтобы ошибка лучшей сложности была ненулевой
+        let runs = runs(|n| n * (1.0 + 0.1 * (n / 1000.0).log2().sin()));
+        let config = FitConfig {
+            complexities: vec![
+                Complexity::ON,
+                Complexity::ONPow(0.999),
+                Complexity::ONCubed,
+            ],
+            ..FitConfig::default()
+        };
+        let fit = LeastSquares::computate_big_o(&runs, |run| run.min, |_| 0.0, &config);
+
+        assert_eq!(fit.complexity, Complexity::ON);
+        assert_eq!(fit.ambiguous, Some(Complexity::ONPow(0.999)));
+        assert_eq!(fit.candidates.len(), 3);
+        assert_eq!(fit.candidates[2].complexity, Complexity::ONCubed);
+
+        let confidence: f64 = fit.candidates.iter().map(|c| c.confidence).sum();
+        assert!((confidence - 1.0).abs() < 1e-9);
+        assert!(fit.candidates[0].confidence > fit.candidates[1].confidence);
+    }
+
+    #[test]
+    fn bootstrap_test() {
+        let runs: Vec<Run> = (0..6)
+            .map(|i| {
+                let len = 1000 << i;
+                let mut run = Run {
+                    len,
+                    ..Run::default()
+                };
+                for noise in &[0.95, 1.0, 1.05] {
+                    run.update(&Sample {
+                        wall: (len * len) as f64 * noise,
+                        ..Sample::default()
+                    });
+                }
+                run
+            })
+            .collect();
+        let config = FitConfig {
+            bootstrap: 200,
+            ..FitConfig::default()
+        };
+        let fit = LeastSquares::computate_big_o(&runs, |run| run.min, |_| 0.0, &config);
+        let bootstrap = fit.bootstrap.unwrap();
+
+        assert_eq!(fit.complexity, Complexity::ONSquared);
+        assert_eq!(bootstrap.resamples, 200);
+        assert_eq!(bootstrap.wins[0], (Complexity::ONSquared, 200));
+        assert!(bootstrap.coef_interval.0 >= 0.95 - 1e-9);
+        assert!(bootstrap.coef_interval.1 <= 1.05 + 1e-9);
+    }
+
+    #[test]
+    fn robust_fit_test() {
+        //Одно поколение замерено на порядок медленнее
+        let mut runs = runs(|n| 3.0 * n * n);
+        runs[6].min *= 10.0;
+
+        let fit =
+            LeastSquares::computate_big_o(&runs, |run| run.min, |_| 0.0, &FitConfig::default());
+        assert_ne!(fit.complexity, Complexity::ONSquared);
+
+        for &estimator in &[Estimator::TheilSen, Estimator::Huber] {
+            for &fit_mode in &[FitMode::Origin, FitMode::Affine] {
+                let config = FitConfig {
+                    estimator,
+                    fit_mode,
+                    ..FitConfig::default()
+                };
+                let fit = LeastSquares::computate_big_o(&runs, |run| run.min, |_| 0.0, &config);
+
+                assert_eq!(fit.complexity, Complexity::ONSquared);
+                assert!((fit.coef - 3.0).abs() < 1e-3);
+            }
+        }
+    }
+
+    #[test]
+    fn weighted_fit_test() {
+        //Замеры поколения: значение ± 1%, выборочное стандартное отклонение пропорционально значению.
+        //Последнее поколение вдвое медленнее
+        let generations = |spread: f64| -> Vec<Run> {
+            (0..8)
+                .map(|i| {
+                    let len = 1000 << i;
+                    let value = (len as f64).powi(2) * if i == 7 { 2.0 } else { 1.0 };
+                    let mut run = Run {
+                        len,
+                        ..Run::default()
+                    };
+                    for &wall in &[value * (1.0 - spread), value * (1.0 + spread)] {
+                        run.update(&Sample {
+                            wall,
+                            ..Sample::default()
+                        });
+                    }
+                    run
+                })
+                .collect()
+        };
+        let value = |run: &Run| run.statistic(Metric::Wall, Statistic::Mean);
+        let spread = |run: &Run| run.std_dev(Metric::Wall);
+
+        let runs = generations(0.01);
+        let config = FitConfig {
+            complexities: vec![Complexity::ONSquared],
+            ..FitConfig::default()
+        };
+        let uniform = LeastSquares::computate_big_o(&runs, value, spread, &config);
+        assert!(uniform.coef > 1.5);
+
+        for &weighting in &[Weighting::InverseVariance, Weighting::Relative] {
+            let config = FitConfig {
+                weighting,
+                ..config.clone()
+            };
+            let fit = LeastSquares::computate_big_o(&runs, value, spread, &config);
+
+            assert!((fit.coef - 7.5 / 7.25).abs() < 1e-9);
+        }
+
+        //Без разброса веса не определены, результат совпадает с равными весами
+        let config = FitConfig {
+            weighting: Weighting::InverseVariance,
+            ..config
+        };
+        let fit = LeastSquares::computate_big_o(&generations(0.0), value, spread, &config);
+        assert_eq!(fit.coef, uniform.coef);
+    }
+
+    #[test]
+    fn information_criterion_test() {
+        //Постоянное время с шумом: смещение и любая растущая функция лишь подгоняются под шум
+        let runs = runs(|n| 100.0 + ((n / 1000.0).log2() as i32 % 2) as f64);
+        let config = FitConfig {
+            fit_mode: FitMode::Affine,
+            complexities: vec![Complexity::O1, Complexity::OLogN, Complexity::ON],
+            ..FitConfig::default()
+        };
+
+        let fit = LeastSquares::computate_big_o(&runs, |run| run.min, |_| 0.0, &config);
+        assert_ne!(fit.complexity, Complexity::O1);
+        assert!(fit.candidates.iter().all(|c| c.ic.is_none()));
+
+        for &selection in &[Selection::Aic, Selection::Bic] {
+            let config = FitConfig {
+                selection,
+                ..config.clone()
+            };
+            let fit = LeastSquares::computate_big_o(&runs, |run| run.min, |_| 0.0, &config);
+
+            assert_eq!(fit.complexity, Complexity::O1);
+            assert_eq!(fit.selection, selection);
+            assert!(fit.candidates.iter().all(|c| c.ic.is_some()));
+            assert!(fit.candidates[0].confidence > fit.candidates[1].confidence);
+        }
+    }
+
+    #[test]
+    fn multi_variable_fit_test() {
+        //Переменные растут независимо, иначе N·M неотличимо от N^2
+        let sizes = [
+            (100, 10),
+            (200, 10),
+            (200, 40),
+            (400, 20),
+            (800, 80),
+            (1600, 40),
+        ];
+        let runs = |time: fn(f64, f64) -> f64| -> Vec<Run> {
+            sizes
+                .iter()
+                .map(|&(n, m)| Run {
+                    min: time(n as f64, m as f64),
+                    len: n + m,
+                    sizes: vec![n, m],
+                    ..Run::default()
+                })
+                .collect()
+        };
+        let config = FitConfig::default();
+
+        let fit = LeastSquares::computate_big_o(
+            &runs(|n, m| 2.0 * n * m),
+            |run| run.min,
+            |_| 0.0,
+            &config,
+        );
+        assert_eq!(fit.complexity, Complexity::ONTimesM(0, 1));
+        assert_eq!(fit.complexity.to_string(), "O(N·M)");
+        assert!((fit.coef - 2.0).abs() < 1e-9);
+
+        //O(N+M) совпадает с O(S) от суммарной длины, остается только сложность от нескольких переменных
+        let fit =
+            LeastSquares::computate_big_o(&runs(|n, m| n + m), |run| run.min, |_| 0.0, &config);
+        assert_eq!(fit.complexity, Complexity::ONPlusM(0, 1));
+        assert!(fit
+            .candidates
+            .iter()
+            .all(|c| c.complexity != Complexity::ON));
+        assert_eq!(fit.ambiguous, None);
+
+        let fit = LeastSquares::computate_big_o(
+            &runs(|n, m| n * m.log2()),
+            |run| run.min,
+            |_| 0.0,
+            &config,
+        );
+        assert_eq!(fit.complexity, Complexity::ONLogM(0, 1));
+        assert_eq!(fit.complexity.to_string(), "O(N·logM)");
+
+        //С одной переменной сложности от нескольких переменных не подходят
+        let fit = LeastSquares::computate_big_o(
+            &super::tests::runs(|n| n),
+            |run| run.min,
+            |_| 0.0,
+            &config,
+        );
+        assert!(fit.candidates.iter().all(|c| !matches!(
+            c.complexity,
+            Complexity::ONTimesM(..) | Complexity::ONPlusM(..) | Complexity::ONLogM(..)
+        )));
+
+        //Переменные растут пропорционально: сложности от нескольких переменных неразличимы
+        let collinear: Vec<Run> = (0..6)
+            .map(|i| {
+                let (n, m) = (100 << i, 10 << i);
+                Run {
+                    min: 2.0 * (n * m) as f64,
+                    len: n + m,
+                    sizes: vec![n, m],
+                    ..Run::default()
+                }
+            })
+            .collect();
+        let fit = LeastSquares::computate_big_o(&collinear, |run| run.min, |_| 0.0, &config);
+        assert_eq!(fit.complexity, Complexity::ONSquared);
+        assert!(fit
+            .candidates
+            .iter()
+            .all(|c| c.complexity.variables().is_none()));
+        assert_ne!(fit.ambiguous, Some(Complexity::ONTimesM(0, 1)));
+
+        let complexity: Complexity = serde_json::from_str(r#"{"ONLogM":[1,0]}"#).unwrap();
+        assert_eq!(complexity.to_string(), "O(M·logN)");
+        assert_eq!(complexity.name_with("S"), "O(M·logN)");
+        assert_eq!(Complexity::ONLogN.name_with("S"), "O(SlogS)");
     }
 }
@@ -1,12 +1,17 @@
 //! Описание аргумента массив.
 
-use crate::configs::{generate_array, ArgumentGenerator, Range, Value};
-use rand::distributions::{Alphanumeric, Uniform};
+use crate::configs::{
+    format_array, generate_array, sample_unique, AlphanumericChar, ArgumentGenerator, Order, Range,
+    Value, ValueDistribution,
+};
+use rand::distributions::Uniform;
+use rand::RngCore;
 use serde::Deserialize;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
 /// Тип аргумента массив.
 #[derive(Deserialize, Validate)]
+#[validate(schema(function = "validate_unique"))]
 pub struct ArrayConfig {
     /// Тип содержимого массива.
     #[validate]
@@ -15,6 +20,15 @@ pub struct ArrayConfig {
     #[serde(flatten)]
     #[validate]
     range: Range,
+    /// Порядок значений. По умолчанию [`Order::Random`].
+    #[serde(default)]
+    #[validate]
+    order: Order,
+    /// Различные значения. Только для [`Value::Int`], диапазон которого не меньше `max(start, end)`.
+    #[serde(default)]
+    unique: bool,
+    /// Зерно генератора случайных чисел. Необязательное, по умолчанию выводится из зерна конфигурации.
+    seed: Option<u64>,
 }
 
 impl ArgumentGenerator for ArrayConfig {
@@ -26,15 +40,107 @@ impl ArgumentGenerator for ArrayConfig {
         self.range.next()
     }
 
-    fn generate(&self) -> String {
+    fn generate(&self, rng: &mut dyn RngCore) -> String {
         match self.value {
-            Value::Int { min, max } => generate_array(self.len(), Uniform::new_inclusive(min, max)),
-            Value::Float { min, max } => {
-                generate_array(self.len(), Uniform::new_inclusive(min, max))
+            Value::Int { min, max, .. } if self.unique => {
+                let values = sample_unique(rng, min, max, self.len());
+                format_array(rng, values, self.order)
             }
-            Value::Char => generate_array(self.len(), Alphanumeric),
+            Value::Int {
+                min,
+                max,
+                distribution,
+            } => match distribution.bounded_int(min, max) {
+                Some(bounded) => generate_array::<i64, _>(rng, self.len(), bounded, self.order),
+                None => generate_array(
+                    rng,
+                    self.len(),
+                    Uniform::new_inclusive(min, max),
+                    self.order,
+                ),
+            },
+            Value::Float {
+                min,
+                max,
+                distribution,
+            } => match distribution.bounded(min, max) {
+                Some(bounded) => generate_array::<f64, _>(rng, self.len(), bounded, self.order),
+                None => generate_array(
+                    rng,
+                    self.len(),
+                    Uniform::new_inclusive(min, max),
+                    self.order,
+                ),
+            },
+            Value::Char => generate_array(rng, self.len(), AlphanumericChar, self.order),
             //TODO: Возможно стоит заменить
-            Value::Bool => generate_array(self.len(), Uniform::new_inclusive(0, 1)),
+            Value::Bool => {
+                generate_array(rng, self.len(), Uniform::new_inclusive(0, 1), self.order)
+            }
+        }
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+}
+
+/// Проверяет, что `unique` задан только для [`Value::Int`] с равномерным распределением
+/// и диапазоном не меньше наибольшей длины `max(range.start, range.end)`, и не вместе с [`Order::AllEqual`].
+fn validate_unique(array: &ArrayConfig) -> Result<(), ValidationError> {
+    if !array.unique {
+        return Ok(());
+    }
+
+    //Первое поколение использует `start`, даже если он больше `end`
+    let len = array.range.start.max(array.range.end);
+    match array.value {
+        _ if array.order == Order::AllEqual => Err(ValidationError::new("unique with AllEqual")),
+        Value::Int {
+            distribution: ValueDistribution::Uniform,
+            min,
+            max,
+        } if (max as i128 - min as i128 + 1) >= len as i128 => Ok(()),
+        Value::Int {
+            distribution: ValueDistribution::Uniform,
+            ..
+        } => {
+            let mut error = ValidationError::new("range of values < max(start, end)");
+            error.add_param("len".into(), &len);
+
+            Err(error)
+        }
+        Value::Int { .. } => Err(ValidationError::new("unique with non-uniform distribution")),
+        _ => Err(ValidationError::new("unique for non-Int value")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::configs::ArrayConfig;
+    use validator::Validate;
+
+    #[test]
+    fn validate_unique_test() {
+        let valid = [
+            r#"{"value":{"type":"Int","min":1,"max":1000},"end":1000,"unique":true}"#,
+            r#"{"value":{"type":"Float"},"start":10}"#,
+        ];
+        for json in &valid {
+            let array: ArrayConfig = serde_json::from_str(json).unwrap();
+            assert!(array.validate().is_ok(), "{}", json);
+        }
+
+        let invalid = [
+            r#"{"value":{"type":"Int","min":1,"max":999},"end":1000,"unique":true}"#,
+            r#"{"value":{"type":"Int","min":1,"max":100},"unique":true}"#,
+            r#"{"value":{"type":"Int","min":1,"max":100},"start":200,"end":100,"unique":true}"#,
+            r#"{"value":{"type":"Float"},"end":10,"unique":true}"#,
+            r#"{"value":{"type":"Int"},"end":10,"unique":true,"order":"AllEqual"}"#,
+        ];
+        for json in &invalid {
+            let array: ArrayConfig = serde_json::from_str(json).unwrap();
+            assert!(array.validate().is_err(), "{}", json);
         }
     }
 }
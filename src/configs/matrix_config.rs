@@ -1,7 +1,8 @@
 //! Описание аргумента матрицы.
 
-use crate::configs::{generate_matrix, ArgumentGenerator, Range, Value};
-use rand::distributions::{Alphanumeric, Uniform};
+use crate::configs::{generate_matrix, AlphanumericChar, ArgumentGenerator, Range, Value};
+use rand::distributions::Uniform;
+use rand::RngCore;
 use serde::Deserialize;
 use validator::Validate;
 
@@ -17,6 +18,8 @@ pub struct MatrixConfig {
     /// Размер столбцов.
     #[validate]
     columns: Range,
+    /// Зерно генератора случайных чисел. Необязательное, по умолчанию выводится из зерна конфигурации.
+    seed: Option<u64>,
 }
 
 impl ArgumentGenerator for MatrixConfig {
@@ -28,21 +31,56 @@ impl ArgumentGenerator for MatrixConfig {
         self.rows.next() * self.columns.next()
     }
 
-    fn generate(&self) -> String {
+    fn sizes(&self) -> Vec<usize> {
+        vec![self.rows.start, self.columns.start]
+    }
+
+    fn size_names(&self) -> Vec<&'static str> {
+        vec!["rows", "columns"]
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    fn generate(&self, rng: &mut dyn RngCore) -> String {
         match self.value {
-            Value::Int { min, max } => generate_matrix(
-                self.rows.start,
-                self.columns.start,
-                Uniform::new_inclusive(min, max),
-            ),
-            Value::Float { min, max } => generate_matrix(
-                self.rows.start,
-                self.columns.start,
-                Uniform::new_inclusive(min, max),
-            ),
-            Value::Char => generate_matrix(self.rows.start, self.columns.start, Alphanumeric),
+            Value::Int {
+                min,
+                max,
+                distribution,
+            } => match distribution.bounded_int(min, max) {
+                Some(bounded) => {
+                    generate_matrix::<i64, _>(rng, self.rows.start, self.columns.start, bounded)
+                }
+                None => generate_matrix(
+                    rng,
+                    self.rows.start,
+                    self.columns.start,
+                    Uniform::new_inclusive(min, max),
+                ),
+            },
+            Value::Float {
+                min,
+                max,
+                distribution,
+            } => match distribution.bounded(min, max) {
+                Some(bounded) => {
+                    generate_matrix::<f64, _>(rng, self.rows.start, self.columns.start, bounded)
+                }
+                None => generate_matrix(
+                    rng,
+                    self.rows.start,
+                    self.columns.start,
+                    Uniform::new_inclusive(min, max),
+                ),
+            },
+            Value::Char => {
+                generate_matrix(rng, self.rows.start, self.columns.start, AlphanumericChar)
+            }
             //TODO: Возможно стоит заменить
             Value::Bool => generate_matrix(
+                rng,
                 self.rows.start,
                 self.columns.start,
                 Uniform::new_inclusive(0, 1),
@@ -2,18 +2,28 @@
 
 pub mod array_config;
 pub mod matrix_config;
+pub mod permutation_config;
 pub mod range_config;
+pub mod string_config;
 
 #[doc(inline)]
 pub use crate::configs::array_config::ArrayConfig;
 #[doc(inline)]
 pub use crate::configs::matrix_config::MatrixConfig;
 #[doc(inline)]
+pub use crate::configs::permutation_config::PermutationConfig;
+#[doc(inline)]
 pub use crate::configs::range_config::RangeConfig;
+#[doc(inline)]
+pub use crate::configs::string_config::StringConfig;
 
-use rand::distributions::Distribution;
-use rand::Rng;
+use rand::distributions::{Alphanumeric, Distribution};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_distr::{Exp, Geometric, Normal, Zipf};
 use serde::Deserialize;
+use std::collections::HashSet;
 
 use validator::{Validate, ValidationError, ValidationErrors};
 
@@ -23,8 +33,20 @@ pub trait ArgumentGenerator {
     fn len(&self) -> usize;
     ///Увеличивает длину аргументов, после чего возвращается её.
     fn next_len(&mut self) -> usize;
-    ///Генерирует новые значения.
-    fn generate(&self) -> String;
+    ///Генерирует новые значения с помощью генератора случайных чисел `rng`.
+    fn generate(&self, rng: &mut dyn RngCore) -> String;
+    ///Возвращает зерно генератора случайных чисел аргумента, если оно задано.
+    fn seed(&self) -> Option<u64> {
+        None
+    }
+    ///Возвращает размеры аргумента по независимым переменным.
+    fn sizes(&self) -> Vec<usize> {
+        vec![self.len()]
+    }
+    ///Возвращает названия размеров из [`ArgumentGenerator::sizes`].
+    fn size_names(&self) -> Vec<&'static str> {
+        vec!["len"]
+    }
 }
 
 //Костыль. Нельзя просто так сделать десериализацию в Vec<dyn ArgumentGenerator>
@@ -36,6 +58,8 @@ pub enum Config {
     Array(ArrayConfig),
     Matrix(MatrixConfig),
     Range(RangeConfig),
+    Permutation(PermutationConfig),
+    String(StringConfig),
 }
 
 impl Validate for Config {
@@ -44,6 +68,8 @@ impl Validate for Config {
             Config::Array(array) => array.validate(),
             Config::Matrix(matrix) => matrix.validate(),
             Config::Range(range) => range.validate(),
+            Config::Permutation(permutation) => permutation.validate(),
+            Config::String(string) => string.validate(),
         }
     }
 }
@@ -60,6 +86,9 @@ pub enum Value {
         /// Максимальное занчение. `max` >= `min`.
         #[serde(default = "Value::int_max")]
         max: i64,
+        /// Распределение значений. По умолчанию [`ValueDistribution::Uniform`].
+        #[serde(default)]
+        distribution: ValueDistribution,
     },
     /// Вещественое число.
     Float {
@@ -69,6 +98,9 @@ pub enum Value {
         /// Максимальное занчение. `max` >= `min`.
         #[serde(default = "Value::float_max")]
         max: f64,
+        /// Распределение значений. По умолчанию [`ValueDistribution::Uniform`].
+        #[serde(default)]
+        distribution: ValueDistribution,
     },
     /// Символ: a-z, A-Z, 0-9.
     Char,
@@ -79,7 +111,11 @@ pub enum Value {
 impl Validate for Value {
     fn validate(&self) -> Result<(), ValidationErrors> {
         match self {
-            Value::Int { min, max } => {
+            Value::Int {
+                min,
+                max,
+                distribution,
+            } => {
                 if min > max {
                     let mut errors = ValidationErrors::new();
                     let mut error = ValidationError::new("min > max");
@@ -88,19 +124,31 @@ impl Validate for Value {
 
                     Err(errors)
                 } else {
-                    Ok(())
+                    distribution.validate()
                 }
             }
-            Value::Float { min, max } => {
+            Value::Float {
+                min,
+                max,
+                distribution,
+            } => {
                 if min > max {
                     let mut errors = ValidationErrors::new();
                     let mut error = ValidationError::new("min > max");
                     error.add_param("min".into(), min);
                     errors.add("Value::Float", error);
 
+                    Err(errors)
+                } else if distribution.is_shifted() && *min + 1.0 == *min {
+                    //Сдвиг от `min` теряется, все значения совпали бы с `min`
+                    let mut errors = ValidationErrors::new();
+                    let mut error = ValidationError::new("shifted distribution with unbounded min");
+                    error.add_param("min".into(), min);
+                    errors.add("Value::Float", error);
+
                     Err(errors)
                 } else {
-                    Ok(())
+                    distribution.validate()
                 }
             }
             _ => Ok(()),
@@ -129,6 +177,360 @@ impl Value {
     }
 }
 
+/// Распределение значений [`Value::Int`] и [`Value::Float`].
+/// Значения, выходящие за `[min, max]`, заменяются ближайшей границей.
+#[derive(Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum ValueDistribution {
+    /// Равномерное распределение на `[min, max]`.
+    Uniform,
+    /// Нормальное распределение. `std_dev` >= 0.
+    Normal { mean: f64, std_dev: f64 },
+    /// Экспоненциальное распределение, сдвинутое на `min`. `lambda` > 0.
+    Exponential { lambda: f64 },
+    /// Распределение Ципфа на `min`, `min + 1`, ..., `max`: значение `min + k - 1` выпадает с вероятностью,
+    /// пропорциональной `1 / k^s`. `s` >= 0.
+    Zipf { s: f64 },
+    /// Геометрическое распределение, сдвинутое на `min`: количество неудач до первого успеха с вероятностью `p`.
+    /// `p` из (0, 1].
+    Geometric { p: f64 },
+}
+
+impl Default for ValueDistribution {
+    fn default() -> Self {
+        ValueDistribution::Uniform
+    }
+}
+
+impl Validate for ValueDistribution {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let (name, valid) = match *self {
+            ValueDistribution::Uniform => return Ok(()),
+            ValueDistribution::Normal { mean, std_dev } => (
+                "ValueDistribution::Normal",
+                mean.is_finite() && std_dev.is_finite() && std_dev >= 0.0,
+            ),
+            ValueDistribution::Exponential { lambda } => {
+                ("ValueDistribution::Exponential", lambda > 0.0)
+            }
+            ValueDistribution::Zipf { s } => ("ValueDistribution::Zipf", s.is_finite() && s >= 0.0),
+            ValueDistribution::Geometric { p } => {
+                ("ValueDistribution::Geometric", p > 0.0 && p <= 1.0)
+            }
+        };
+
+        if valid {
+            Ok(())
+        } else {
+            let mut errors = ValidationErrors::new();
+            errors.add(name, ValidationError::new("invalid parameters"));
+
+            Err(errors)
+        }
+    }
+}
+
+impl ValueDistribution {
+    /// Создает распределение вещественных значений в `[min, max]`. Параметры должны быть проверены [`Validate`].
+    /// Для [`ValueDistribution::Uniform`] возвращает `None`: используется [`rand::distributions::Uniform`].
+    fn bounded(self, min: f64, max: f64) -> Option<Bounded<f64>> {
+        //Приведение насыщающее, поэтому бесконечная ширина диапазона не переполняет счетчик
+        let count = ((max - min).floor() as u128).saturating_add(1);
+        let shape = self.shape(count)?;
+
+        Some(Bounded { shape, min, max })
+    }
+
+    /// Создает распределение целых значений в `[min, max]`. Параметры должны быть проверены [`Validate`].
+    /// Сдвиг от `min` вычисляется в целых числах, поэтому не теряется при больших по модулю `min`.
+    /// Для [`ValueDistribution::Uniform`] возвращает `None`: используется [`rand::distributions::Uniform`].
+    fn bounded_int(self, min: i64, max: i64) -> Option<Bounded<i64>> {
+        let count = (max as i128 - min as i128 + 1) as u128;
+        let shape = self.shape(count)?;
+
+        Some(Bounded { shape, min, max })
+    }
+
+    /// Распределение с параметрами для `count` возможных значений. Количество значений Ципфа
+    /// не может превышать [`u64::MAX`].
+    fn shape(self, count: u128) -> Option<Shape> {
+        let count = count.min(u64::MAX as u128) as u64;
+        let shape = match self {
+            ValueDistribution::Uniform => return None,
+            ValueDistribution::Normal { mean, std_dev } => {
+                Shape::Normal(Normal::new(mean, std_dev).unwrap())
+            }
+            ValueDistribution::Exponential { lambda } => {
+                Shape::Exponential(Exp::new(lambda).unwrap())
+            }
+            ValueDistribution::Zipf { s } => Shape::Zipf(Zipf::new(count, s).unwrap()),
+            ValueDistribution::Geometric { p } => Shape::Geometric(Geometric::new(p).unwrap()),
+        };
+
+        Some(shape)
+    }
+
+    /// Значения распределения сдвинуты на `min`: все, кроме [`ValueDistribution::Uniform`] и [`ValueDistribution::Normal`].
+    fn is_shifted(self) -> bool {
+        !matches!(
+            self,
+            ValueDistribution::Uniform | ValueDistribution::Normal { .. }
+        )
+    }
+}
+
+/// Символы a-z, A-Z, 0-9 для [`Value::Char`].
+/// [`Alphanumeric`] возвращает байты, которые иначе выводятся числами.
+struct AlphanumericChar;
+
+impl Distribution<char> for AlphanumericChar {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> char {
+        char::from(rng.sample(Alphanumeric))
+    }
+}
+
+/// Распределение [`ValueDistribution`] с параметрами.
+enum Shape {
+    Normal(Normal<f64>),
+    Exponential(Exp<f64>),
+    Zipf(Zipf<f64>),
+    Geometric(Geometric),
+}
+
+/// Распределение значений, ограниченных `[min, max]`.
+struct Bounded<T> {
+    shape: Shape,
+    min: T,
+    max: T,
+}
+
+impl Distribution<f64> for Bounded<f64> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> f64 {
+        let value = match &self.shape {
+            Shape::Normal(normal) => normal.sample(rng),
+            Shape::Exponential(exp) => self.min + exp.sample(rng),
+            Shape::Zipf(zipf) => self.min + zipf.sample(rng) - 1.0,
+            Shape::Geometric(geometric) => self.min + geometric.sample(rng) as f64,
+        };
+
+        value.max(self.min).min(self.max)
+    }
+}
+
+impl Distribution<i64> for Bounded<i64> {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> i64 {
+        //Приведения насыщающие, поэтому значение остается в [min, max]
+        let offset = match &self.shape {
+            Shape::Normal(normal) => {
+                return (normal.sample(rng).round() as i64).clamp(self.min, self.max)
+            }
+            Shape::Exponential(exp) => exp.sample(rng).round() as u64,
+            Shape::Zipf(zipf) => zipf.sample(rng) as u64 - 1,
+            Shape::Geometric(geometric) => geometric.sample(rng),
+        };
+
+        (self.min as i128 + offset as i128).min(self.max as i128) as i64
+    }
+}
+
+/// Порядок значений в [`ArrayConfig`].
+#[derive(Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum Order {
+    /// Случайный порядок.
+    Random,
+    /// По возрастанию.
+    Ascending,
+    /// По убыванию.
+    Descending,
+    /// По возрастанию, после чего `k` случайных пар значений меняются местами.
+    NearlySorted(usize),
+    /// Все значения равны.
+    AllEqual,
+    /// "Органные трубы": по возрастанию до середины, затем по убыванию.
+    OrganPipe,
+    /// "Пила": последовательные отрезки длиной `period`, отсортированные по возрастанию. `period` > 0.
+    Sawtooth(usize),
+}
+
+impl Default for Order {
+    fn default() -> Self {
+        Order::Random
+    }
+}
+
+impl Validate for Order {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        match self {
+            Order::Sawtooth(0) => {
+                let mut errors = ValidationErrors::new();
+                errors.add("Order::Sawtooth", ValidationError::new("period == 0"));
+
+                Err(errors)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Order {
+    /// Упорядочивает случайные значения `values`. Для [`Order::AllEqual`] все значения заменяются первым.
+    fn arrange<T: PartialOrd + Clone>(self, values: &mut [T], rng: &mut dyn RngCore) {
+        let ascending = |a: &T, b: &T| a.partial_cmp(b).unwrap();
+
+        match self {
+            Order::Random => {}
+            Order::Ascending => values.sort_by(ascending),
+            Order::Descending => values.sort_by(|a, b| ascending(b, a)),
+            Order::NearlySorted(k) => {
+                values.sort_by(ascending);
+                if values.len() > 1 {
+                    for _ in 0..k {
+                        let i = rng.gen_range(0..values.len());
+                        let j = rng.gen_range(0..values.len());
+                        values.swap(i, j);
+                    }
+                }
+            }
+            Order::AllEqual => {
+                if let Some(first) = values.first().cloned() {
+                    values.iter_mut().for_each(|value| *value = first.clone());
+                }
+            }
+            Order::OrganPipe => {
+                values.sort_by(ascending);
+                //Четные позиции - на подъем, нечетные - на спуск
+                let (rising, falling): (Vec<_>, Vec<_>) = values
+                    .iter()
+                    .cloned()
+                    .enumerate()
+                    .partition(|(i, _)| i % 2 == 0);
+                let pipe = rising
+                    .into_iter()
+                    .chain(falling.into_iter().rev())
+                    .map(|(_, value)| value);
+                for (value, next) in values.iter_mut().zip(pipe) {
+                    *value = next;
+                }
+            }
+            Order::Sawtooth(period) => values
+                .chunks_mut(period.max(1))
+                .for_each(|tooth| tooth.sort_by(ascending)),
+        }
+    }
+}
+
+/// Алфавит символов [`StringConfig`].
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+pub enum Alphabet {
+    /// Символы `0`, `1`.
+    Binary,
+    /// Символы `A`, `C`, `G`, `T`.
+    Dna,
+    /// Символы a-z.
+    Lowercase,
+    /// Произвольный непустой набор символов без пробельных. Повторы не учитываются.
+    Custom(String),
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Alphabet::Lowercase
+    }
+}
+
+impl Validate for Alphabet {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        match self {
+            Alphabet::Custom(symbols)
+                if symbols.is_empty() || symbols.chars().any(char::is_whitespace) =>
+            {
+                let mut errors = ValidationErrors::new();
+                errors.add(
+                    "Alphabet::Custom",
+                    ValidationError::new("empty or contains whitespace"),
+                );
+
+                Err(errors)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Alphabet {
+    /// Возвращает различные символы алфавита в порядке их появления.
+    fn symbols(&self) -> Vec<char> {
+        match self {
+            Alphabet::Binary => vec!['0', '1'],
+            Alphabet::Dna => vec!['A', 'C', 'G', 'T'],
+            Alphabet::Lowercase => ('a'..='z').collect(),
+            Alphabet::Custom(symbols) => {
+                let mut unique = HashSet::new();
+                symbols.chars().filter(|c| unique.insert(*c)).collect()
+            }
+        }
+    }
+}
+
+/// Структура символов строк [`StringConfig`].
+#[derive(Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum Pattern {
+    /// Случайные символы.
+    Random,
+    /// Случайный блок длиной `period`, повторенный до длины строки. `period` > 0.
+    Periodic(usize),
+    /// Как [`Pattern::Periodic`], но последний символ заменен другим символом алфавита,
+    /// например `aaa...ab`. Худший случай для наивного поиска подстроки. `period` > 0.
+    AlmostPeriodic(usize),
+}
+
+impl Default for Pattern {
+    fn default() -> Self {
+        Pattern::Random
+    }
+}
+
+impl Validate for Pattern {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        match self {
+            Pattern::Periodic(0) | Pattern::AlmostPeriodic(0) => {
+                let mut errors = ValidationErrors::new();
+                errors.add("Pattern", ValidationError::new("period == 0"));
+
+                Err(errors)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Pattern {
+    /// Дописывает в `result` строку длиной `len` из символов `symbols` со структурой `self`.
+    /// Для [`Pattern::AlmostPeriodic`] в `symbols` должно быть не меньше двух символов.
+    fn fill(self, rng: &mut dyn RngCore, result: &mut String, len: usize, symbols: &[char]) {
+        if len == 0 {
+            return;
+        }
+        let random = |rng: &mut dyn RngCore| *symbols.choose(rng).unwrap();
+
+        let period = match self {
+            Pattern::Random => {
+                result.extend((0..len).map(|_| random(rng)));
+                return;
+            }
+            Pattern::Periodic(period) | Pattern::AlmostPeriodic(period) => period.min(len),
+        };
+
+        let block: Vec<char> = (0..period).map(|_| random(rng)).collect();
+        result.extend(block.iter().cycle().take(len));
+
+        if let (Pattern::AlmostPeriodic(_), Some(&last)) = (self, block.get((len - 1) % period)) {
+            let others: Vec<char> = symbols.iter().copied().filter(|&c| c != last).collect();
+            result.pop();
+            result.push(*others.choose(rng).unwrap());
+        }
+    }
+}
+
 /// Диапазон натуральных значений.
 #[derive(Deserialize, Validate, Copy, Clone)]
 pub struct Range {
@@ -169,37 +571,92 @@ impl Range {
     }
 }
 
-/// Генерация массива длиной `len`, с распределением `distr`.
-fn generate_array<T, D>(len: usize, distr: D) -> String
+/// Смешивает зерно `seed` со значением `value`.
+/// Используется, чтобы получить независимые зерна аргументов и поколений из одного зерна.
+/// Зерно перемешивается до объединения со значением, поэтому пары с одинаковым `seed ^ value` не совпадают.
+pub fn mix_seed(seed: u64, value: u64) -> u64 {
+    splitmix(splitmix(seed) ^ value)
+}
+
+/// Один шаг генератора SplitMix64 из состояния `x`.
+fn splitmix(x: u64) -> u64 {
+    let mut z = x.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// Создает генератор случайных чисел поколения `gen` для аргумента с зерном `seed`.
+pub fn seeded_rng(seed: u64, gen: usize) -> StdRng {
+    StdRng::seed_from_u64(mix_seed(seed, gen as u64))
+}
+
+/// Генерация массива длиной `len`, с распределением `distr` и порядком `order`.
+fn generate_array<T, D>(rng: &mut dyn RngCore, len: usize, distr: D, order: Order) -> String
 where
-    T: ToString,
+    T: ToString + PartialOrd + Clone,
     D: Distribution<T>,
 {
-    let mut result = len.to_string();
-    generate(&mut result, len, distr);
+    let values: Vec<T> = (&mut *rng).sample_iter(distr).take(len).collect();
+    format_array(rng, values, order)
+}
+
+/// Упорядочивает значения `values` в порядке `order` и записывает их в строку вместе с длиной.
+fn format_array<T>(rng: &mut dyn RngCore, mut values: Vec<T>, order: Order) -> String
+where
+    T: ToString + PartialOrd + Clone,
+{
+    order.arrange(&mut values, rng);
+
+    let mut result = values.len().to_string();
+    for val in values {
+        result.push(' ');
+        result.push_str(&val.to_string());
+    }
 
     result
 }
 
+/// Выбирает `len` различных целых чисел из `[min, max]` алгоритмом Флойда и перемешивает их.
+/// Диапазон должен содержать не меньше `len` чисел.
+fn sample_unique(rng: &mut dyn RngCore, min: i64, max: i64, len: usize) -> Vec<i64> {
+    let span = (max as i128 - min as i128 + 1) as u128;
+    let mut chosen = HashSet::with_capacity(len);
+    let mut values = Vec::with_capacity(len);
+
+    for j in span - len as u128..span {
+        let t = rng.gen_range(0..=j);
+        let value = if chosen.insert(t) {
+            t
+        } else {
+            chosen.insert(j);
+            j
+        };
+        values.push((min as i128 + value as i128) as i64);
+    }
+    values.shuffle(rng);
+
+    values
+}
+
 /// Генерация матрицы `rows`*`columns`, с распределением `distr`.
-fn generate_matrix<T, D>(rows: usize, columns: usize, distr: D) -> String
+fn generate_matrix<T, D>(rng: &mut dyn RngCore, rows: usize, columns: usize, distr: D) -> String
 where
     T: ToString,
     D: Distribution<T>,
 {
-    let mut result = format!("{} {}", rows.to_string(), columns.to_string());
-    generate(&mut result, rows * columns, distr);
+    let mut result = format!("{} {}", rows, columns);
+    generate(rng, &mut result, rows * columns, distr);
 
     result
 }
 
 /// Генерация значений в строку `result`, длиной `len`, с распределением `distr`.
-fn generate<T, D>(result: &mut String, len: usize, distr: D)
+fn generate<T, D>(rng: &mut dyn RngCore, result: &mut String, len: usize, distr: D)
 where
     T: ToString,
     D: Distribution<T>,
 {
-    let rng = rand::thread_rng();
     let rng_iter = rng.sample_iter(distr).take(len).map(|v| v.to_string());
 
     for val in rng_iter {
@@ -210,11 +667,144 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::configs::Config;
+    use crate::configs::{
+        sample_unique, seeded_rng, ArgumentGenerator, Config, Order, Value, ValueDistribution,
+    };
+    use rand::distributions::Distribution;
+    use std::collections::HashSet;
+    use validator::Validate;
 
     #[test]
     fn deserialization_test() {
         let json = r#"[{"Range":{"start":10,"end":1000,"multiplier":2}},{"Array":{"value":{"type":"Int","min":0,"max":100},"start":10,"end":1000,"multiplier":2}}]"#;
-        let _from_json: Vec<Config> = serde_json::from_str(&json).unwrap();
+        let _from_json: Vec<Config> = serde_json::from_str(json).unwrap();
+    }
+
+    #[test]
+    fn seed_test() {
+        let json = r#"{"value":{"type":"Int","min":0,"max":100},"start":100,"seed":42}"#;
+        let array: crate::configs::ArrayConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(array.seed(), Some(42));
+
+        let first = array.generate(&mut seeded_rng(42, 0));
+        assert_eq!(first, array.generate(&mut seeded_rng(42, 0)));
+        assert_ne!(first, array.generate(&mut seeded_rng(42, 1)));
+        assert_ne!(first, array.generate(&mut seeded_rng(43, 0)));
+
+        //Соседние зерна в соседних поколениях не совпадают
+        assert_ne!(
+            array.generate(&mut seeded_rng(42, 1)),
+            array.generate(&mut seeded_rng(43, 0))
+        );
+    }
+
+    #[test]
+    fn order_test() {
+        let mut rng = seeded_rng(0, 0);
+        let arrange = |order: Order, rng: &mut rand::rngs::StdRng| {
+            let mut values = vec![3, 1, 4, 1, 5, 9, 2, 6];
+            order.arrange(&mut values, rng);
+            values
+        };
+
+        assert_eq!(arrange(Order::Random, &mut rng), [3, 1, 4, 1, 5, 9, 2, 6]);
+        assert_eq!(
+            arrange(Order::Ascending, &mut rng),
+            [1, 1, 2, 3, 4, 5, 6, 9]
+        );
+        assert_eq!(
+            arrange(Order::Descending, &mut rng),
+            [9, 6, 5, 4, 3, 2, 1, 1]
+        );
+        assert_eq!(arrange(Order::AllEqual, &mut rng), [3; 8]);
+        assert_eq!(
+            arrange(Order::OrganPipe, &mut rng),
+            [1, 2, 4, 6, 9, 5, 3, 1]
+        );
+        assert_eq!(
+            arrange(Order::Sawtooth(3), &mut rng),
+            [1, 3, 4, 1, 5, 9, 2, 6]
+        );
+        assert_eq!(
+            arrange(Order::NearlySorted(0), &mut rng),
+            [1, 1, 2, 3, 4, 5, 6, 9]
+        );
+
+        let mut nearly = arrange(Order::NearlySorted(2), &mut rng);
+        nearly.sort_unstable();
+        assert_eq!(nearly, [1, 1, 2, 3, 4, 5, 6, 9]);
+        assert!(Order::Sawtooth(0).validate().is_err());
+    }
+
+    #[test]
+    fn sample_unique_test() {
+        let mut rng = seeded_rng(0, 0);
+
+        let values = sample_unique(&mut rng, -5, 4, 10);
+        let mut sorted = values.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, (-5..=4).collect::<Vec<_>>());
+
+        let values = sample_unique(&mut rng, i64::MIN, i64::MAX, 1000);
+        assert_eq!(values.iter().collect::<HashSet<_>>().len(), 1000);
+    }
+
+    #[test]
+    fn distribution_test() {
+        let mut rng = seeded_rng(0, 0);
+        let distributions = [
+            ValueDistribution::Normal {
+                mean: 50.0,
+                std_dev: 30.0,
+            },
+            ValueDistribution::Exponential { lambda: 0.1 },
+            ValueDistribution::Zipf { s: 1.2 },
+            ValueDistribution::Geometric { p: 0.3 },
+        ];
+
+        for distribution in &distributions {
+            let bounded = distribution.bounded_int(10, 100).unwrap();
+            for _ in 0..1000 {
+                let value: i64 = bounded.sample(&mut rng);
+                assert!((10..=100).contains(&value), "{:?}: {}", distribution, value);
+            }
+        }
+        for distribution in &distributions {
+            let bounded = distribution.bounded(10.0, f64::MAX).unwrap();
+            for _ in 0..1000 {
+                let value: f64 = bounded.sample(&mut rng);
+                assert!(value >= 10.0 && value.is_finite(), "{:?}", distribution);
+            }
+        }
+        assert!(ValueDistribution::Uniform.bounded(10.0, 100.0).is_none());
+
+        //Границы Int по умолчанию: сдвиг от min не теряется
+        for distribution in &distributions {
+            let bounded = distribution.bounded_int(i64::MIN, i64::MAX).unwrap();
+            let values: HashSet<i64> = (0..100).map(|_| bounded.sample(&mut rng)).collect();
+            assert!(values.len() > 1, "{:?}", distribution);
+        }
+
+        //Для Float со сдвигом от min граница должна быть конечной
+        let json = r#"{"type":"Float","distribution":{"Zipf":{"s":1.2}}}"#;
+        let value: Value = serde_json::from_str(json).unwrap();
+        assert!(value.validate().is_err());
+
+        //Ципф: наименьшее значение самое частое
+        let zipf = ValueDistribution::Zipf { s: 1.5 }
+            .bounded_int(1, 100)
+            .unwrap();
+        let ones = (0..1000)
+            .filter(|_| Distribution::<i64>::sample(&zipf, &mut rng) == 1)
+            .count();
+        assert!(ones > 300);
+
+        let json = r#"{"type":"Int","min":0,"max":10,"distribution":{"Geometric":{"p":1.5}}}"#;
+        let value: Value = serde_json::from_str(json).unwrap();
+        assert!(value.validate().is_err());
+
+        let json = r#"{"type":"Float","distribution":{"Normal":{"mean":0,"std_dev":1}}}"#;
+        let value: Value = serde_json::from_str(json).unwrap();
+        assert!(value.validate().is_ok());
     }
 }
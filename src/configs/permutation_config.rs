@@ -0,0 +1,39 @@
+//! Описание аргумента перестановки.
+
+use crate::configs::{format_array, ArgumentGenerator, Order, Range};
+use rand::seq::SliceRandom;
+use rand::RngCore;
+use serde::Deserialize;
+use validator::Validate;
+
+/// Тип аргумента перестановка: числа от 1 до N в случайном порядке.
+#[derive(Deserialize, Validate)]
+pub struct PermutationConfig {
+    /// Размер перестановки.
+    #[serde(flatten)]
+    #[validate]
+    range: Range,
+    /// Зерно генератора случайных чисел. Необязательное, по умолчанию выводится из зерна конфигурации.
+    seed: Option<u64>,
+}
+
+impl ArgumentGenerator for PermutationConfig {
+    fn len(&self) -> usize {
+        self.range.start
+    }
+
+    fn next_len(&mut self) -> usize {
+        self.range.next()
+    }
+
+    fn generate(&self, rng: &mut dyn RngCore) -> String {
+        let mut values: Vec<usize> = (1..=self.len()).collect();
+        values.shuffle(&mut *rng);
+
+        format_array(rng, values, Order::Random)
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+}
@@ -1,6 +1,7 @@
 //! Описание аргумента диапазона.
 
 use crate::configs::{ArgumentGenerator, Range};
+use rand::RngCore;
 use serde::Deserialize;
 use validator::Validate;
 
@@ -21,7 +22,11 @@ impl ArgumentGenerator for RangeConfig {
         self.range.next()
     }
 
-    fn generate(&self) -> String {
+    fn generate(&self, _rng: &mut dyn RngCore) -> String {
         self.range.start.to_string()
     }
+
+    fn size_names(&self) -> Vec<&'static str> {
+        vec!["value"]
+    }
 }
@@ -0,0 +1,165 @@
+//! Описание аргумента строки.
+
+use crate::configs::{Alphabet, ArgumentGenerator, Pattern, Range};
+use rand::distributions::Uniform;
+use rand::{Rng, RngCore};
+use serde::Deserialize;
+use validator::{Validate, ValidationError};
+
+/// Тип аргумента строка: одна строка длиной N или N слов, разделенных пробелами.
+#[derive(Deserialize, Validate)]
+#[validate(schema(function = "validate_alphabet"))]
+pub struct StringConfig {
+    /// Длина строки или количество слов, если задан `words`.
+    #[serde(flatten)]
+    #[validate]
+    range: Range,
+    /// Алфавит символов. По умолчанию [`Alphabet::Lowercase`].
+    #[serde(default)]
+    #[validate]
+    alphabet: Alphabet,
+    /// Длины слов. Необязательный, по умолчанию генерируется одна строка.
+    #[validate]
+    words: Option<Words>,
+    /// Структура символов строки или каждого слова. По умолчанию [`Pattern::Random`].
+    #[serde(default)]
+    #[validate]
+    pattern: Pattern,
+    /// Зерно генератора случайных чисел. Необязательное, по умолчанию выводится из зерна конфигурации.
+    seed: Option<u64>,
+}
+
+/// Диапазон длин слов [`StringConfig`].
+#[derive(Deserialize, Validate, Copy, Clone)]
+#[validate(schema(function = "validate_words"))]
+pub struct Words {
+    /// Минимальная длина слова. Минимальное значение 1.
+    #[validate(range(min = 1))]
+    min_len: usize,
+    /// Максимальная длина слова. `max_len` >= `min_len`.
+    max_len: usize,
+}
+
+impl ArgumentGenerator for StringConfig {
+    fn len(&self) -> usize {
+        self.range.start
+    }
+
+    fn next_len(&mut self) -> usize {
+        self.range.next()
+    }
+
+    fn generate(&self, rng: &mut dyn RngCore) -> String {
+        let symbols = self.alphabet.symbols();
+        let mut result = self.len().to_string();
+
+        match self.words {
+            Some(words) => {
+                let lens = Uniform::new_inclusive(words.min_len, words.max_len);
+                for _ in 0..self.len() {
+                    let len = rng.sample(lens);
+                    result.push(' ');
+                    self.pattern.fill(rng, &mut result, len, &symbols);
+                }
+            }
+            None => {
+                result.push(' ');
+                self.pattern.fill(rng, &mut result, self.len(), &symbols);
+            }
+        }
+
+        result
+    }
+
+    fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+}
+
+/// Проверяет, что в алфавите не меньше двух символов для [`Pattern::AlmostPeriodic`].
+fn validate_alphabet(string: &StringConfig) -> Result<(), ValidationError> {
+    match string.pattern {
+        Pattern::AlmostPeriodic(_) if string.alphabet.symbols().len() < 2 => Err(
+            ValidationError::new("AlmostPeriodic with less than 2 symbols"),
+        ),
+        _ => Ok(()),
+    }
+}
+
+/// Проверяет, что `min_len` <= `max_len`.
+fn validate_words(words: &Words) -> Result<(), ValidationError> {
+    if words.min_len > words.max_len {
+        let mut error = ValidationError::new("min_len > max_len");
+        error.add_param("min_len".into(), &words.min_len);
+
+        Err(error)
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::configs::{seeded_rng, ArgumentGenerator, StringConfig};
+    use validator::Validate;
+
+    fn parse(json: &str) -> StringConfig {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn validate_test() {
+        let valid = [
+            r#"{"start":10}"#,
+            r#"{"alphabet":"Dna","words":{"min_len":1,"max_len":1}}"#,
+            r#"{"alphabet":{"Custom":"xyz"},"pattern":{"AlmostPeriodic":3}}"#,
+        ];
+        for json in &valid {
+            assert!(parse(json).validate().is_ok(), "{}", json);
+        }
+
+        let invalid = [
+            r#"{"alphabet":{"Custom":""}}"#,
+            r#"{"alphabet":{"Custom":"a b"}}"#,
+            r#"{"alphabet":{"Custom":"aa"},"pattern":{"AlmostPeriodic":1}}"#,
+            r#"{"pattern":{"Periodic":0}}"#,
+            r#"{"words":{"min_len":0,"max_len":5}}"#,
+            r#"{"words":{"min_len":6,"max_len":5}}"#,
+        ];
+        for json in &invalid {
+            assert!(parse(json).validate().is_err(), "{}", json);
+        }
+    }
+
+    #[test]
+    fn generate_test() {
+        let mut rng = seeded_rng(0, 0);
+
+        let string = parse(r#"{"start":100,"alphabet":"Dna"}"#).generate(&mut rng);
+        let mut parts = string.splitn(2, ' ');
+        assert_eq!(parts.next(), Some("100"));
+        let text = parts.next().unwrap();
+        assert_eq!(text.len(), 100);
+        assert!(text.chars().all(|c| "ACGT".contains(c)));
+
+        let string = parse(r#"{"start":10,"alphabet":"Binary","pattern":{"Periodic":3}}"#)
+            .generate(&mut rng);
+        let text: Vec<char> = string.split(' ').nth(1).unwrap().chars().collect();
+        assert_eq!(text.len(), 10);
+        assert!((3..10).all(|i| text[i] == text[i - 3]));
+
+        let string =
+            parse(r#"{"start":8,"alphabet":{"Custom":"ab"},"pattern":{"AlmostPeriodic":1}}"#)
+                .generate(&mut rng);
+        let text = string.split(' ').nth(1).unwrap();
+        assert!(text == "aaaaaaab" || text == "bbbbbbba", "{}", text);
+
+        let string = parse(r#"{"start":50,"words":{"min_len":2,"max_len":4}}"#).generate(&mut rng);
+        let words: Vec<&str> = string.split(' ').collect();
+        assert_eq!(words[0], "50");
+        assert_eq!(words.len(), 51);
+        assert!(words[1..]
+            .iter()
+            .all(|w| (2..=4).contains(&w.len()) && w.chars().all(|c| c.is_ascii_lowercase())));
+    }
+}
@@ -3,6 +3,7 @@ extern crate serde;
 extern crate serde_json;
 extern crate validator;
 
+mod child;
 mod complexity;
 mod configs;
 mod program;
@@ -14,6 +15,7 @@ use clap::{App, Arg};
 use crate::complexity::LeastSquares;
 use crate::program::Program;
 use crate::report::Report;
+use crate::run::Statistic;
 use std::path::Path;
 
 /// Главная функция.
@@ -32,9 +34,24 @@ fn main() {
                 .multiple(true)
                 .help("Path to config file"),
         )
+        .arg(
+            Arg::with_name("fit-statistic")
+                .short("s")
+                .long("fit-statistic")
+                .value_name("STATISTIC")
+                .takes_value(true)
+                .validator(|s| s.parse::<Statistic>().map(|_| ()))
+                .help(
+                    "Statistic used for fitting, overrides config: \
+                    min | mean | median | p90 | p99 | max | trimmed-mean[:FRACTION]",
+                ),
+        )
         .get_matches();
 
     let cfg_paths = matches.values_of_os("cfg").unwrap();
+    let fit_statistic: Option<Statistic> = matches
+        .value_of("fit-statistic")
+        .map(|s| s.parse().unwrap());
 
     for cfg_path in cfg_paths {
         let cfg_path: &Path = cfg_path.as_ref();
@@ -50,6 +67,10 @@ fn main() {
             }
         };
 
+        if let Some(fit_statistic) = fit_statistic {
+            program.set_fit_statistic(fit_statistic);
+        }
+
         let runs = match program.exec() {
             Ok(runs) => runs,
             Err(error) => {
@@ -62,8 +83,27 @@ fn main() {
             }
         };
 
-        let least_sq = LeastSquares::computate_big_o(&runs);
-        let report = Report::new(program.path(), cfg_path, runs, least_sq);
+        let metric = program.metric();
+        let statistic = program.fit_statistic();
+        let fit = program.fit();
+        let time = LeastSquares::computate_big_o(
+            &runs,
+            |run| run.statistic(metric, statistic),
+            |run| run.std_dev(metric),
+            fit,
+        );
+        let space = LeastSquares::computate_big_o(&runs, |run| run.rss as f64, |_| 0.0, fit);
+        let report = Report::new(
+            program.path(),
+            cfg_path,
+            runs,
+            metric,
+            statistic,
+            time,
+            space,
+        )
+        .with_seed(program.seed())
+        .with_variables(program.variables());
 
         println!("{}\n", report);
     }
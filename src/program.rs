@@ -1,17 +1,23 @@
 //! Запуск и замеры времени выполенения пользовательской программы.
 
-use crate::configs::{ArgumentGenerator, Config};
-use crate::run::Run;
+use crate::child;
+use crate::complexity::{variable_name, FitConfig, TOTAL_NAME};
+use crate::configs::{mix_seed, seeded_rng, ArgumentGenerator, Config};
+use crate::run::{Metric, Outliers, Run, Sample, Statistic};
 
+use rand::rngs::StdRng;
+use rand::Rng;
 use serde::Deserialize;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::fs::File;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
-use std::time::Instant;
-use std::{fmt, process};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+use std::{fmt, io, process, thread};
 
 /// Возможный вариант ошибки.
 #[derive(Debug)]
@@ -22,6 +28,12 @@ pub enum ErrorKind {
     NotSuccessful(Option<i32>),
     /// Ошибка при записи аргументов в промежуточный файл.
     CantWriteArgs(PathBuf, std::io::Error),
+    /// Ошибка при записи аргументов в стандартный поток ввода.
+    CantWriteStdin(std::io::Error),
+    /// Пользовательская программа не завершилась за отведенное время. Длина аргументов поколения.
+    TimedOut(usize),
+    /// Ошибка при ожидании завершения запущенной пользовательской программы.
+    CantWait(std::io::Error),
 }
 
 /// Ошибка, которая может возникнуть при вызове метода [`Program::exec`].
@@ -52,6 +64,27 @@ impl Error {
             kind: ErrorKind::CantWriteArgs(path, error),
         }
     }
+
+    /// Создание ошибки `kind`: [`ErrorKind::CantWriteStdin`].
+    pub fn cant_write_stdin(error: std::io::Error) -> Self {
+        Error {
+            kind: ErrorKind::CantWriteStdin(error),
+        }
+    }
+
+    /// Создание ошибки `kind`: [`ErrorKind::TimedOut`].
+    pub fn timed_out(len: usize) -> Self {
+        Error {
+            kind: ErrorKind::TimedOut(len),
+        }
+    }
+
+    /// Создание ошибки `kind`: [`ErrorKind::CantWait`].
+    pub fn cant_wait(error: std::io::Error) -> Self {
+        Error {
+            kind: ErrorKind::CantWait(error),
+        }
+    }
 }
 
 impl std::error::Error for Error {}
@@ -70,6 +103,15 @@ impl Display for Error {
                     io_error
                 )
             }
+            ErrorKind::CantWriteStdin(io_error) => {
+                write!(f, "Can't write arguments to stdin. {}", io_error)
+            }
+            ErrorKind::TimedOut(len) => {
+                write!(f, "Program timed out. Arguments length '{}'", len)
+            }
+            ErrorKind::CantWait(io_error) => {
+                write!(f, "Can't wait for program to finish. {}", io_error)
+            }
             ErrorKind::NotSuccessful(status) => match status {
                 Some(code) => write!(
                     f,
@@ -82,6 +124,91 @@ impl Display for Error {
     }
 }
 
+/// Способ передачи сгенерированных значений пользовательской программе.
+#[derive(Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum InputMode {
+    /// Путь до промежуточного файла передается в качестве аргумента.
+    File,
+    /// Значения передаются через стандартный поток ввода.
+    Stdin,
+    /// Значения записываются в промежуточный файл и передаются через стандартный поток ввода.
+    Both,
+}
+
+impl Default for InputMode {
+    fn default() -> Self {
+        InputMode::File
+    }
+}
+
+impl InputMode {
+    /// Нужно ли записывать значения в промежуточный файл.
+    fn uses_file(self) -> bool {
+        self != InputMode::Stdin
+    }
+
+    /// Нужно ли передавать значения через стандартный поток ввода.
+    fn uses_stdin(self) -> bool {
+        self != InputMode::File
+    }
+}
+
+/// Адаптивное количество итераций: запуски в поколении повторяются,
+/// пока доверительный интервал статистики `fit_statistic` не станет достаточно узким.
+#[derive(Deserialize, Validate, Copy, Clone)]
+#[validate(schema(function = "validate_adaptive_iters"))]
+pub struct Adaptive {
+    /// Требуемая относительная полуширина доверительного интервала. Например, 0.02 - ±2%.
+    #[validate(range(min = 0.0))]
+    target: f64,
+    /// Минимальное количество итераций. По умолчанию 3.
+    #[serde(default = "Adaptive::min_iters_default")]
+    #[validate(range(min = 2))]
+    min_iters: usize,
+    /// Максимальное количество итераций. По умолчанию 100.
+    #[serde(default = "Adaptive::max_iters_default")]
+    max_iters: usize,
+    /// Ограничение суммарного времени запусков в поколении в секундах, после которого итерации прекращаются. Необязательное.
+    #[validate(range(min = 0.0))]
+    budget_secs: Option<f64>,
+    /// Уровень доверия интервала, полуширина которого сравнивается с `target`. По умолчанию 0.95.
+    #[serde(default = "Adaptive::confidence_level_default")]
+    #[validate(range(min = 0.0, max = 1.0))]
+    confidence_level: f64,
+}
+
+impl Adaptive {
+    /// Количество повторных выборок бутстрепа при оценке доверительного интервала.
+    const RESAMPLES: usize = 200;
+
+    /// Возвращает значение `min_iters` по умолчанию - 3. Используется [`serde`].
+    const fn min_iters_default() -> usize {
+        3
+    }
+
+    /// Возвращает значение `max_iters` по умолчанию - 100. Используется [`serde`].
+    const fn max_iters_default() -> usize {
+        100
+    }
+
+    /// Возвращает значение `confidence_level` по умолчанию - 0.95. Используется [`serde`].
+    const fn confidence_level_default() -> f64 {
+        0.95
+    }
+}
+
+/// Проверяет, что `max_iters` >= `min_iters`.
+fn validate_adaptive_iters(adaptive: &Adaptive) -> Result<(), ValidationError> {
+    if adaptive.max_iters < adaptive.min_iters {
+        let mut error = ValidationError::new("max_iters < min_iters");
+        error.add_param("max_iters".into(), &adaptive.max_iters);
+
+        Err(error)
+    } else {
+        Ok(())
+    }
+}
+
 //Специальная стуктура, с помощью который валидируются данные,
 // производит преобразование с Vec<Config> в Generators
 /// Описание конфигурационного файла.
@@ -98,8 +225,69 @@ struct ProgramConfig {
     #[validate(range(min = 1))]
     gens: usize,
     /// Количество итераций в поколении. Генерация значений одинаковой длины. Минимальное значение 1.
+    /// Не используется, если задан `adaptive`.
     #[validate(range(min = 1))]
     iters: usize,
+    /// Адаптивное количество итераций. Необязательное.
+    #[validate]
+    adaptive: Option<Adaptive>,
+    /// Количество прогревочных запусков в поколении перед замерами. По умолчанию 0.
+    #[serde(default)]
+    warmup: usize,
+    /// Сохранять время прогревочных запусков для вывода в отчете.
+    #[serde(default)]
+    show_warmup: bool,
+    /// Зерно генератора случайных чисел. Необязательное, по умолчанию выбирается случайно.
+    seed: Option<u64>,
+    /// Способ передачи значений. По умолчанию [`InputMode::File`].
+    #[serde(default)]
+    input_mode: InputMode,
+    /// Шаблон аргументов командной строки. Подстановки описаны в [`expand`].
+    /// По умолчанию передается только путь до промежуточного файла, если его требует `input_mode`.
+    #[serde(default)]
+    argv: Option<Vec<String>>,
+    /// Переменные окружения. Значения поддерживают те же подстановки, что и `argv`.
+    #[serde(default)]
+    env: HashMap<String, String>,
+    /// Ограничение времени одного запуска программы в секундах. Необязательное.
+    /// Не больше 10^9: большие значения не представимы в [`Duration`].
+    #[validate(range(min = 0.0, max = 1e9))]
+    timeout_secs: Option<f64>,
+    /// Ограничение суммарного времени запусков в одном поколении в секундах. Необязательное.
+    /// Не больше 10^9, как и `timeout_secs`.
+    #[validate(range(min = 0.0, max = 1e9))]
+    gen_timeout_secs: Option<f64>,
+    /// Прекратить увеличение длины после превышения ограничения времени
+    /// и вычислить сложность по завершившимся поколениям. Иначе возвращается ошибка [`ErrorKind::TimedOut`].
+    #[serde(default)]
+    stop_on_timeout: bool,
+    /// Величина, по которой вычисляется сложность. По умолчанию [`Metric::Wall`].
+    #[serde(default)]
+    metric: Metric,
+    /// Статистика замеров поколения, по которой вычисляется сложность. По умолчанию [`Statistic::Min`].
+    #[serde(default)]
+    #[validate]
+    fit_statistic: Statistic,
+    /// Способ отбрасывания выбросов среди замеров поколения. Необязательный.
+    #[validate]
+    outliers: Option<Outliers>,
+    /// Настройки вычисления сложности.
+    #[serde(flatten)]
+    #[validate]
+    fit: FitConfig,
+    /// Вычитать из замеров накладные расходы, измеренные холостой программой `calibration_path`.
+    #[serde(default)]
+    calibrate: bool,
+    /// Путь до холостой программы. По умолчанию `/bin/true`.
+    #[serde(default = "ProgramConfig::calibration_path_default")]
+    calibration_path: PathBuf,
+}
+
+impl ProgramConfig {
+    /// Возвращает значение `calibration_path` по умолчанию - `/bin/true`. Используется [`serde`].
+    fn calibration_path_default() -> PathBuf {
+        PathBuf::from("/bin/true")
+    }
 }
 
 type Generators = Vec<Box<dyn ArgumentGenerator>>;
@@ -116,6 +304,36 @@ pub struct Program {
     gens: usize,
     /// Количество итераций в поколении. Генерация значений одинаковой длины.
     iters: usize,
+    /// Адаптивное количество итераций.
+    adaptive: Option<Adaptive>,
+    /// Количество прогревочных запусков в поколении.
+    warmup: usize,
+    /// Сохранять время прогревочных запусков.
+    show_warmup: bool,
+    /// Зерно генератора случайных чисел.
+    seed: u64,
+    /// Способ передачи значений.
+    input_mode: InputMode,
+    /// Шаблон аргументов командной строки.
+    argv: Option<Vec<String>>,
+    /// Переменные окружения.
+    env: HashMap<String, String>,
+    /// Ограничение времени одного запуска программы.
+    timeout: Option<Duration>,
+    /// Ограничение суммарного времени запусков в одном поколении.
+    gen_timeout: Option<Duration>,
+    /// Прекратить увеличение длины после превышения ограничения времени.
+    stop_on_timeout: bool,
+    /// Величина, по которой вычисляется сложность.
+    metric: Metric,
+    /// Статистика замеров поколения, по которой вычисляется сложность.
+    fit_statistic: Statistic,
+    /// Способ отбрасывания выбросов.
+    outliers: Option<Outliers>,
+    /// Настройки вычисления сложности.
+    fit: FitConfig,
+    /// Путь до холостой программы, если требуется калибровка.
+    calibration_path: Option<PathBuf>,
 }
 
 impl From<ProgramConfig> for Program {
@@ -128,6 +346,8 @@ impl From<ProgramConfig> for Program {
                     Config::Array(array) => Box::new(array),
                     Config::Matrix(matrix) => Box::new(matrix),
                     Config::Range(range) => Box::new(range),
+                    Config::Permutation(permutation) => Box::new(permutation),
+                    Config::String(string) => Box::new(string),
                 };
                 config
             })
@@ -139,6 +359,25 @@ impl From<ProgramConfig> for Program {
             args,
             gens: config.gens,
             iters: config.iters,
+            adaptive: config.adaptive,
+            warmup: config.warmup,
+            show_warmup: config.show_warmup,
+            seed: config.seed.unwrap_or_else(|| rand::thread_rng().gen()),
+            input_mode: config.input_mode,
+            argv: config.argv,
+            env: config.env,
+            timeout: config.timeout_secs.map(Duration::from_secs_f64),
+            gen_timeout: config.gen_timeout_secs.map(Duration::from_secs_f64),
+            stop_on_timeout: config.stop_on_timeout,
+            metric: config.metric,
+            fit_statistic: config.fit_statistic,
+            outliers: config.outliers,
+            fit: config.fit,
+            calibration_path: if config.calibrate {
+                Some(config.calibration_path)
+            } else {
+                None
+            },
         }
     }
 }
@@ -160,83 +399,442 @@ impl Program {
 
         Ok(program_config.into())
     }
-    /// Генерирует входные аргументы с помощью типажа [`ArgumentGenerator`].
-    /// После чего, запускает пользовательскую программу `path`, передавая в качестве аргумента путь до промежуточного файла `path_to_temp/...txt`.
-    /// Замеряет время выполнения программы с помощью [`Instant`].
+    /// Генерирует входные аргументы с помощью типажа [`ArgumentGenerator`] и для каждого поколения
+    /// запускает пользовательскую программу `path`: сначала `warmup` прогревочных запусков, затем замеры.
+    /// Если программа не уложилась в ограничение времени и не задан `stop_on_timeout`, возвращается ошибка [`ErrorKind::TimedOut`].
     pub fn exec(&mut self) -> Result<Vec<Run>, Error> {
         let mut runs = Vec::with_capacity(self.gens);
 
         for gen in 0..self.gens {
-            let mut run = Run::default();
-
-            run.len = match gen {
-                0 => self.args.iter().map(|x| x.len()).sum(),
-                _ => self.args.iter_mut().map(|x| x.next_len()).sum(),
+            let lens: Vec<usize> = match gen {
+                0 => self.args.iter().map(|x| x.len()).collect(),
+                _ => self.args.iter_mut().map(|x| x.next_len()).collect(),
+            };
+            let mut run = Run {
+                len: lens.iter().sum(),
+                sizes: self.args.iter().flat_map(|x| x.sizes()).collect(),
+                ..Run::default()
             };
+            let mut overhead = Run::default();
+            let mut spent = Duration::default();
+            let mut rng = rand::thread_rng();
+            let mut rngs = self.rngs(gen, false);
+            let mut warmup_rngs = self.rngs(gen, true);
 
-            for iter in 0..self.iters {
+            for iter in 0..self.warmup {
                 let file_name = format!(
-                    "{}/generation_{}_interation{}.txt",
+                    "{}/generation_{}_warmup{}.txt",
                     self.path_to_temp.display(),
                     gen,
                     iter
                 );
                 let path = Path::new(&file_name);
-                self.write_args_to_file(path)?;
 
-                let start_time = Instant::now();
-                let command = process::Command::new(&self.path)
-                    .arg(&path)
-                    .output()
-                    .map_err(Error::failed_to_start)?;
-                let duration = start_time.elapsed();
+                let (values, input) = self.prepare_args(path, &mut warmup_rngs)?;
+                let command = self.command(&self.path, path, &lens, &values);
+                match self.launch(command, input, self.timeout)? {
+                    Some(sample) if self.show_warmup => run.warmup.push(sample.wall),
+                    Some(_) => {}
+                    None => {
+                        run.timed_out = true;
+                        break;
+                    }
+                }
+            }
 
-                if !command.status.success() {
-                    return Err(Error::not_successful(command.status.code()));
+            for iter in 0.. {
+                if run.timed_out || !self.needs_more(&run, spent, &mut rng) {
+                    break;
                 }
 
-                run.update(duration.as_secs_f64());
+                let file_name = format!(
+                    "{}/generation_{}_interation{}.txt",
+                    self.path_to_temp.display(),
+                    gen,
+                    iter
+                );
+                let path = Path::new(&file_name);
+
+                let (values, input) = self.prepare_args(path, &mut rngs)?;
+                if let Some(calibration_path) = &self.calibration_path {
+                    let command = self.command(calibration_path, path, &lens, &values);
+                    //Прерванная калибровка обрабатывается как прерванный замер, с учетом `stop_on_timeout`
+                    match self.launch(command, input.clone(), self.timeout)? {
+                        Some(sample) => overhead.update(&sample),
+                        None => {
+                            run.timed_out = true;
+                            break;
+                        }
+                    }
+                }
+
+                let command = self.command(&self.path, path, &lens, &values);
+                let sample = match self.launch(command, input, self.limit(spent))? {
+                    Some(sample) => sample,
+                    None => {
+                        run.timed_out = true;
+                        break;
+                    }
+                };
+
+                spent += Duration::from_secs_f64(sample.wall);
+                run.update(&sample);
+            }
+
+            if run.timed_out && !self.stop_on_timeout {
+                return Err(Error::timed_out(run.len));
             }
-            run.avg /= self.iters as f64;
+
+            if let Some(outliers) = self.outliers {
+                run.reject_outliers(self.metric, outliers);
+            }
+            run.avg /= run.iters().max(1) as f64;
+            if self.calibration_path.is_some() && run.iters() > 0 {
+                run.subtract_overhead(&overhead);
+            }
+
+            let timed_out = run.timed_out;
             runs.push(run);
+
+            if timed_out {
+                break;
+            }
         }
 
         Ok(runs)
     }
 
+    /// Генераторы случайных чисел аргументов в поколении `gen`. Зерно выводится из зерна аргумента
+    /// (или `seed` и индекса аргумента) и номера поколения, поэтому любое поколение можно воспроизвести.
+    /// Прогревочные запуски (`warmup`) используют отдельные генераторы, поэтому их количество не меняет значения замеров.
+    #[doc(hidden)]
+    fn rngs(&self, gen: usize, warmup: bool) -> Vec<StdRng> {
+        //Номер потока прогревочных значений, не совпадающий с индексами аргументов
+        const WARMUP_STREAM: u64 = u64::MAX;
+
+        self.args
+            .iter()
+            .enumerate()
+            .map(|(i, arg)| {
+                let seed = arg.seed().unwrap_or_else(|| mix_seed(self.seed, i as u64));
+                let seed = if warmup {
+                    mix_seed(seed, WARMUP_STREAM)
+                } else {
+                    seed
+                };
+                seeded_rng(seed, gen)
+            })
+            .collect()
+    }
+
+    /// Возвращает `seed`.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
     /// Возвращает `path`.
     pub fn path(&self) -> &Path {
         &self.path
     }
 
+    /// Описание переменных сложности в порядке [`Run::sizes`]: имя переменной, индекс и размер аргумента.
+    /// Например, `M = arg0 columns`. Если переменных несколько, первой описывается суммарная длина аргументов
+    /// [`TOTAL_NAME`], от которой зависят сложности от одной переменной.
+    pub fn variables(&self) -> Vec<String> {
+        let mut variables: Vec<String> = self
+            .args
+            .iter()
+            .enumerate()
+            .flat_map(|(i, arg)| {
+                arg.size_names()
+                    .into_iter()
+                    .map(move |name| format!("arg{} {}", i, name))
+            })
+            .enumerate()
+            .map(|(index, size)| format!("{} = {}", variable_name(index), size))
+            .collect();
+
+        if variables.len() > 1 {
+            let total: Vec<String> = self
+                .args
+                .iter()
+                .enumerate()
+                .map(|(i, arg)| format!("arg{} {}", i, arg.size_names().join("·")))
+                .collect();
+            variables.insert(0, format!("{} = {}", TOTAL_NAME, total.join(" + ")));
+        }
+
+        variables
+    }
+
+    /// Заменяет `fit_statistic`.
+    pub fn set_fit_statistic(&mut self, fit_statistic: Statistic) {
+        self.fit_statistic = fit_statistic;
+    }
+
+    /// Возвращает `metric`.
+    pub fn metric(&self) -> Metric {
+        self.metric
+    }
+
+    /// Возвращает `fit_statistic`.
+    pub fn fit_statistic(&self) -> Statistic {
+        self.fit_statistic
+    }
+
+    /// Возвращает `fit`.
+    pub fn fit(&self) -> &FitConfig {
+        &self.fit
+    }
+
+    /// Генерирует значения всех аргументов генераторами случайных чисел `rngs`.
+    #[doc(hidden)]
+    fn generate_args(&self, rngs: &mut [StdRng]) -> Vec<String> {
+        self.args
+            .iter()
+            .zip(rngs)
+            .map(|(x, rng)| x.generate(rng))
+            .collect()
+    }
+
+    /// Генерирует значения всех аргументов и, если требуется, записывает их в промежуточный файл `path`.
+    /// Возвращает значения и данные для стандартного потока ввода. Запись файла в замер не входит.
+    #[doc(hidden)]
+    fn prepare_args(
+        &self,
+        path: &Path,
+        rngs: &mut [StdRng],
+    ) -> Result<(Vec<String>, Vec<u8>), Error> {
+        let values = self.generate_args(rngs);
+        let input = values.join(" ").into_bytes(); //Разделитель между значениями
+        if self.writes_file() {
+            self.write_args_to_file(path, &input)?;
+        }
+
+        Ok((values, input))
+    }
+
+    /// Нужно ли записывать промежуточный файл: его требует `input_mode` или на него ссылаются шаблоны.
+    #[doc(hidden)]
+    fn writes_file(&self) -> bool {
+        const FILE: &str = "{file}";
+
+        self.input_mode.uses_file()
+            || self.env.values().any(|v| v.contains(FILE))
+            || self.argv.iter().flatten().any(|a| a.contains(FILE))
+    }
+
     #[doc(hidden)]
-    fn write_args_to_file<P>(&self, path: P) -> Result<(), Error>
+    fn write_args_to_file<P>(&self, path: P, buf: &[u8]) -> Result<(), Error>
     where
         P: AsRef<Path>,
     {
         let path = path.as_ref();
 
-        let args: Vec<String> = self.args.iter().map(|x| x.generate()).collect();
-        let buf: Vec<u8> = args.join(" ").into_bytes(); //Разделитель между значениями
-
         let mut file =
             File::create(path).map_err(|e| Error::cant_write_args(path.to_path_buf(), e))?;
-        file.write_all(&buf)
+        file.write_all(buf)
             .map_err(|e| Error::cant_write_args(path.to_path_buf(), e))?;
 
         Ok(())
     }
+
+    /// Нужна ли еще одна итерация в поколении `run`, на которое уже потрачено `spent`.
+    /// Без `adaptive` выполняется ровно `iters` итераций. Иначе итерации повторяются от `min_iters` до `max_iters`,
+    /// пока не исчерпан `budget_secs` и относительная полуширина доверительного интервала `fit_statistic`
+    /// уровня `confidence_level` больше `target`.
+    #[doc(hidden)]
+    fn needs_more<R: Rng>(&self, run: &Run, spent: Duration, rng: &mut R) -> bool {
+        let adaptive = match &self.adaptive {
+            Some(adaptive) => adaptive,
+            None => return run.iters() < self.iters,
+        };
+
+        if run.iters() < adaptive.min_iters {
+            return true;
+        }
+
+        let over_budget = adaptive
+            .budget_secs
+            .map_or(false, |budget| spent.as_secs_f64() >= budget);
+        if run.iters() >= adaptive.max_iters || over_budget {
+            return false;
+        }
+
+        let ci = run.relative_ci(
+            self.metric,
+            self.fit_statistic,
+            adaptive.confidence_level,
+            Adaptive::RESAMPLES,
+            rng,
+        );
+        //NaN, если статистика равна нулю - точнее измерить нельзя
+        ci > adaptive.target
+    }
+
+    /// Ограничение времени очередного запуска с учетом времени `spent`, уже потраченного в поколении:
+    /// меньшее из `timeout` и остатка `gen_timeout`.
+    #[doc(hidden)]
+    fn limit(&self, spent: Duration) -> Option<Duration> {
+        let remaining = self
+            .gen_timeout
+            .map(|limit| limit.checked_sub(spent).unwrap_or_default());
+
+        match (self.timeout, remaining) {
+            (Some(timeout), Some(remaining)) => Some(timeout.min(remaining)),
+            (timeout, remaining) => timeout.or(remaining),
+        }
+    }
+
+    /// Подготавливает команду запуска программы `bin`: аргументы и переменные окружения по шаблонам, стандартный поток ввода.
+    #[doc(hidden)]
+    fn command(
+        &self,
+        bin: &Path,
+        path: &Path,
+        lens: &[usize],
+        values: &[String],
+    ) -> process::Command {
+        let mut command = process::Command::new(bin);
+        command.stdout(Stdio::null()).stderr(Stdio::null());
+
+        match &self.argv {
+            Some(argv) => {
+                command.args(argv.iter().map(|a| expand(a, path, lens, values)));
+            }
+            None if self.input_mode.uses_file() => {
+                command.arg(path);
+            }
+            None => {}
+        }
+
+        command.envs(
+            self.env
+                .iter()
+                .map(|(k, v)| (k, expand(v, path, lens, values))),
+        );
+
+        if self.input_mode.uses_stdin() {
+            command.stdin(Stdio::piped());
+        } else {
+            command.stdin(Stdio::null());
+        }
+
+        command
+    }
+
+    /// Запускает пользовательскую программу и возвращает замер её выполнения
+    /// или `None`, если программа не уложилась в `timeout` и была завершена принудительно.
+    /// Время выполнения замеряется с помощью [`Instant`], процессорное время и пиковая память - с помощью `wait4`.
+    /// При необходимости `input` передается через стандартный поток ввода из отдельного потока.
+    #[doc(hidden)]
+    fn launch(
+        &self,
+        mut command: process::Command,
+        input: Vec<u8>,
+        timeout: Option<Duration>,
+    ) -> Result<Option<Sample>, Error> {
+        let start_time = Instant::now();
+        let mut child = command.spawn().map_err(Error::failed_to_start)?;
+
+        let writer = child.stdin.take().map(|mut stdin| {
+            thread::spawn(move || match stdin.write_all(&input) {
+                //Программа может не дочитать ввод до конца
+                Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+                result => result,
+            })
+        });
+
+        let exit = child::wait(&mut child, timeout).map_err(Error::cant_wait)?;
+        let duration = start_time.elapsed();
+
+        if let Some(writer) = writer {
+            writer
+                .join()
+                .expect("stdin writer thread panicked")
+                .map_err(Error::cant_write_stdin)?;
+        }
+
+        if exit.timed_out {
+            return Ok(None);
+        }
+
+        if !exit.status.success() {
+            return Err(Error::not_successful(exit.status.code()));
+        }
+
+        Ok(Some(Sample {
+            wall: duration.as_secs_f64(),
+            user: exit.user.as_secs_f64(),
+            sys: exit.sys.as_secs_f64(),
+            rss: exit.rss,
+        }))
+    }
+}
+
+/// Подставляет значения в шаблон `template`:
+/// * `{file}` - путь до промежуточного файла;
+/// * `{len}` - суммарная длина всех аргументов;
+/// * `{lenI}` - длина `I`-го аргумента, начиная с 0;
+/// * `{argI}` - сгенерированное значение `I`-го аргумента.
+fn expand(template: &str, path: &Path, lens: &[usize], values: &[String]) -> String {
+    let total: usize = lens.iter().sum();
+    let mut result = template
+        .replace("{file}", &path.to_string_lossy())
+        .replace("{len}", &total.to_string());
+
+    for (i, len) in lens.iter().enumerate() {
+        result = result.replace(&format!("{{len{}}}", i), &len.to_string());
+    }
+
+    //Значения подставляются последними, чтобы не раскрывать шаблоны внутри них
+    for (i, value) in values.iter().enumerate() {
+        result = result.replace(&format!("{{arg{}}}", i), value);
+    }
+
+    result
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::program::ProgramConfig;
+    use crate::complexity::FitMode;
+    use crate::program::{expand, InputMode, Program, ProgramConfig};
+    use std::fs;
+    use std::path::{Path, PathBuf};
     use validator::Validate;
 
+    /// Аргумент - диапазон со значением 7.
+    const RANGE_7: &str = r#"{"Range":{"start":7}}"#;
+
+    /// Создает пустой временный каталог теста `name`.
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("time_analyzer_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        dir
+    }
+
+    /// Программа, которая дописывает стандартный поток ввода в `dir/stdin.txt`.
+    /// `args` - аргументы, `extra` - дополнительные поля конфигурации.
+    fn cat_program(dir: &Path, args: &str, extra: &str) -> Program {
+        let json = format!(
+            r#"{{"path":"/bin/sh", "path_to_temp":"{0}", "args":[{1}], "gens":1, "iters":1, "argv":["-c", "cat >> {0}/stdin.txt"]{2}}}"#,
+            dir.display(),
+            args,
+            extra
+        );
+        let config: ProgramConfig = serde_json::from_str(&json).unwrap();
+        config.validate().unwrap();
+
+        config.into()
+    }
+
     #[test]
     fn des_test() {
         let json = r#"{"path":"123", "path_to_temp":"456", "args":[], "gens":1, "iters":1}"#;
-        let okay: Result<ProgramConfig, _> = serde_json::from_str(&json);
+        let okay: Result<ProgramConfig, _> = serde_json::from_str(json);
 
         assert!(okay.is_ok());
     }
@@ -244,15 +842,115 @@ mod tests {
     #[test]
     fn des_test_failed() {
         let json = r#"{"path": "123","path_to_temp": "456","args": [{"Array" : {"value" : {"type" : "Double"}}}],"gens": 1,"iters": 1}"#;
-        let error: Result<ProgramConfig, _> = serde_json::from_str(&json);
+        let error: Result<ProgramConfig, _> = serde_json::from_str(json);
 
         assert!(error.is_err());
     }
 
+    #[test]
+    fn des_test_input_mode() {
+        let json = r#"{"path":"123", "path_to_temp":"456", "args":[], "gens":1, "iters":1, "input_mode":"Stdin"}"#;
+        let config: ProgramConfig = serde_json::from_str(json).unwrap();
+
+        assert_eq!(config.input_mode, InputMode::Stdin);
+    }
+
+    #[test]
+    fn exec_test_input_mode() {
+        for &(mode, stdin, file) in &[
+            (InputMode::File, "", true),
+            (InputMode::Stdin, "7", false),
+            (InputMode::Both, "7", true),
+        ] {
+            let dir = temp_dir(&format!("{:?}", mode).to_lowercase());
+            let mut program =
+                cat_program(&dir, RANGE_7, &format!(r#", "input_mode":"{:?}""#, mode));
+            let runs = program.exec().unwrap();
+
+            assert_eq!(runs.len(), 1);
+            assert_eq!(runs[0].iters(), 1);
+            assert_eq!(fs::read_to_string(dir.join("stdin.txt")).unwrap(), stdin);
+            let values = fs::read_to_string(dir.join("generation_0_interation0.txt")).ok();
+            assert_eq!(
+                values,
+                if file { Some("7".to_string()) } else { None },
+                "{:?}",
+                mode
+            );
+
+            fs::remove_dir_all(&dir).unwrap();
+        }
+    }
+
+    #[test]
+    fn exec_test_warmup() {
+        let dir = temp_dir("warmup");
+        let extra = r#", "input_mode":"Stdin", "warmup":2"#;
+
+        let runs = cat_program(&dir, RANGE_7, extra).exec().unwrap();
+        //Прогревочные запуски выполняются, но не попадают в замеры
+        assert_eq!(fs::read_to_string(dir.join("stdin.txt")).unwrap(), "777");
+        assert_eq!(runs[0].iters(), 1);
+        assert!(runs[0].warmup.is_empty());
+
+        let extra = format!(r#"{}, "show_warmup":true"#, extra);
+        let runs = cat_program(&dir, RANGE_7, &extra).exec().unwrap();
+        assert_eq!(runs[0].iters(), 1);
+        assert_eq!(runs[0].warmup.len(), 2);
+
+        //Количество прогревочных запусков не меняет значения замеров
+        let array = r#"{"Array":{"value":{"type":"Int","min":0,"max":1000},"start":20}}"#;
+        let measured = |warmup: usize| {
+            let extra = format!(r#", "seed":42, "warmup":{}"#, warmup);
+            cat_program(&dir, array, &extra).exec().unwrap();
+            fs::read_to_string(dir.join("generation_0_interation0.txt")).unwrap()
+        };
+        assert_eq!(measured(0), measured(3));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn des_test_warmup() {
+        let json = r#"{"path":"123", "path_to_temp":"456", "args":[], "gens":1, "iters":1}"#;
+        let config: ProgramConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.warmup, 0);
+        assert!(!config.show_warmup);
+
+        let json = r#"{"path":"123", "path_to_temp":"456", "args":[], "gens":1, "iters":1, "warmup":3, "show_warmup":true}"#;
+        let config: ProgramConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.warmup, 3);
+        assert!(config.show_warmup);
+    }
+
+    #[test]
+    fn expand_test() {
+        let lens = [4096, 10];
+        let values = vec!["4096".to_string(), "10 1 2 3".to_string()];
+        let path = Path::new("/tmp/gen.txt");
+
+        assert_eq!(expand("--n={len}", path, &lens, &values), "--n=4106");
+        assert_eq!(expand("{len0}x{len1}", path, &lens, &values), "4096x10");
+        assert_eq!(expand("{arg1}", path, &lens, &values), "10 1 2 3");
+        assert_eq!(
+            expand("--input={file}", path, &lens, &values),
+            "--input=/tmp/gen.txt"
+        );
+        assert_eq!(expand("{arg2}", path, &lens, &values), "{arg2}");
+    }
+
+    #[test]
+    fn des_test_fit_mode() {
+        let json = r#"{"path":"123", "path_to_temp":"456", "args":[], "gens":1, "iters":1, "fit_mode":"Affine"}"#;
+        let config: ProgramConfig = serde_json::from_str(json).unwrap();
+
+        assert_eq!(config.fit.fit_mode, FitMode::Affine);
+    }
+
     #[test]
     fn validate_test() {
         let json = r#"{"path": "123","path_to_temp": "456","args": [{"Array" : {"value" : {"type" : "Int"}, "start" : 10}}],"gens": 1,"iters": 1}"#;
-        let config: ProgramConfig = serde_json::from_str(&json).unwrap();
+        let config: ProgramConfig = serde_json::from_str(json).unwrap();
         let okay = config.validate();
 
         assert!(okay.is_ok())
@@ -261,7 +959,7 @@ mod tests {
     #[test]
     fn validate_test_2() {
         let json = r#"{"path":"123", "path_to_temp":"456", "args":[], "gens":1, "iters":1}"#;
-        let config: ProgramConfig = serde_json::from_str(&json).unwrap();
+        let config: ProgramConfig = serde_json::from_str(json).unwrap();
         let okay = config.validate();
 
         assert!(okay.is_ok())
@@ -270,7 +968,7 @@ mod tests {
     #[test]
     fn validate_test_failed() {
         let json = r#"{"path":"123", "path_to_temp":"456", "args":[], "gens":0, "iters":1}"#;
-        let config: ProgramConfig = serde_json::from_str(&json).unwrap();
+        let config: ProgramConfig = serde_json::from_str(json).unwrap();
         let error = config.validate();
 
         assert!(error.is_err())
@@ -279,9 +977,69 @@ mod tests {
     #[test]
     fn validate_test_failed_2() {
         let json = r#"{"path": "123","path_to_temp": "456","args": [{"Array" : {"value" : {"type" : "Int", "min":10,"max":0}, "start" : 0}}],"gens": 1,"iters": 1}"#;
-        let config: ProgramConfig = serde_json::from_str(&json).unwrap();
+        let config: ProgramConfig = serde_json::from_str(json).unwrap();
         let error = config.validate();
 
         assert!(error.is_err());
     }
+
+    #[test]
+    fn validate_test_complexities() {
+        let json = r#"{"path":"123", "path_to_temp":"456", "args":[], "gens":1, "iters":1, "complexities":["ON", {"ONPow":1.5}, "O2N"]}"#;
+        let config: ProgramConfig = serde_json::from_str(json).unwrap();
+
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_test_failed_complexities() {
+        for complexities in &["[]", r#"[{"ONPow":-1}]"#] {
+            let json = format!(
+                r#"{{"path":"123", "path_to_temp":"456", "args":[], "gens":1, "iters":1, "complexities":{}}}"#,
+                complexities
+            );
+            let config: ProgramConfig = serde_json::from_str(&json).unwrap();
+
+            assert!(config.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn validate_test_failed_fit_statistic() {
+        let json = r#"{"path":"123", "path_to_temp":"456", "args":[], "gens":1, "iters":1, "fit_statistic":{"TrimmedMean":0.7}}"#;
+        let config: ProgramConfig = serde_json::from_str(json).unwrap();
+
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_test_adaptive() {
+        let json = r#"{"path":"123", "path_to_temp":"456", "args":[], "gens":1, "iters":1, "adaptive":{"target":0.02, "max_iters":50, "budget_secs":10}}"#;
+        let config: ProgramConfig = serde_json::from_str(json).unwrap();
+        let adaptive = config.adaptive.unwrap();
+
+        assert!(config.validate().is_ok());
+        assert_eq!((adaptive.min_iters, adaptive.max_iters), (3, 50));
+    }
+
+    #[test]
+    fn validate_test_failed_timeout() {
+        let json = r#"{"path":"123", "path_to_temp":"456", "args":[], "gens":1, "iters":1, "timeout_secs":1e20}"#;
+        let config: ProgramConfig = serde_json::from_str(json).unwrap();
+
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_test_failed_adaptive() {
+        let json = r#"{"path":"123", "path_to_temp":"456", "args":[], "gens":1, "iters":1, "adaptive":{"target":0.02, "min_iters":10, "max_iters":5}}"#;
+        let config: ProgramConfig = serde_json::from_str(json).unwrap();
+
+        assert!(config.validate().is_err());
+
+        let json = r#"{"path":"123", "path_to_temp":"456", "args":[], "gens":1, "iters":1, "adaptive":{"target":0.02, "confidence_level":1.5}}"#;
+        let config: ProgramConfig = serde_json::from_str(json).unwrap();
+
+        assert!(config.validate().is_err());
+    }
 }
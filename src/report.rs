@@ -1,7 +1,7 @@
 //! Итоговый отчет.
 
-use crate::complexity::{Complexity, LeastSquares};
-use crate::run::Run;
+use crate::complexity::{Complexity, LeastSquares, TOTAL_NAME};
+use crate::run::{Metric, Run, Statistic};
 use std::fmt;
 use std::fmt::{Debug, Display, Formatter};
 use std::path::{Path, PathBuf};
@@ -15,16 +15,30 @@ pub struct Report {
     cfg_path: PathBuf,
     /// Массив запусков.
     runs: Vec<Run>,
-    /// Итоговая асимптотическая сложность.
-    complexity: Complexity,
-    /// Коэффициент.
-    coef: f64,
-    /// Ошибка.
-    rms: f64,
+    /// Величина, по которой вычислена сложность.
+    metric: Metric,
+    /// Статистика замеров поколения, по которой вычислена сложность.
+    statistic: Statistic,
+    /// Итоговая временная сложность.
+    time: LeastSquares,
+    /// Итоговая сложность по памяти.
+    space: LeastSquares,
+    /// Описание переменных сложности. Если их несколько, первой описана суммарная длина [`TOTAL_NAME`].
+    variables: Vec<String>,
+    /// Зерно генератора случайных чисел.
+    seed: Option<u64>,
 }
 
 impl Report {
-    pub fn new<T, B, C>(bin_path: B, cfg_path: C, runs: T, least_squares: LeastSquares) -> Self
+    pub fn new<T, B, C>(
+        bin_path: B,
+        cfg_path: C,
+        runs: T,
+        metric: Metric,
+        statistic: Statistic,
+        time: LeastSquares,
+        space: LeastSquares,
+    ) -> Self
     where
         T: Into<Vec<Run>>,
         B: AsRef<Path>,
@@ -34,85 +48,336 @@ impl Report {
             bin_path: bin_path.as_ref().to_path_buf(),
             cfg_path: cfg_path.as_ref().to_path_buf(),
             runs: runs.into(),
-            complexity: least_squares.complexity,
-            coef: least_squares.coef,
-            rms: least_squares.rms,
+            metric,
+            statistic,
+            time,
+            space,
+            variables: Vec::new(),
+            seed: None,
         }
     }
+
+    /// Задает зерно генератора случайных чисел, с которым были сгенерированы значения.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Задает описание переменных сложности. Выводится, если переменных больше одной.
+    /// Тогда суммарная длина аргументов в сложностях от одной переменной называется [`TOTAL_NAME`].
+    pub fn with_variables(mut self, variables: Vec<String>) -> Self {
+        self.variables = variables;
+        self
+    }
 }
 
 //TODO: Подумать над выводом
 impl Display for Report {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Binary file: {}\nConfig file: {}",
+            self.bin_path.display(),
+            self.cfg_path.display()
+        )?;
+        if let Some(seed) = self.seed {
+            writeln!(f, "Seed: {}", seed)?;
+        }
+        if self.variables.len() > 1 {
+            writeln!(f, "Variables: {}", self.variables.join(", "))?;
+        }
+
         {
-            let header = format!(
-                "Binary file: {}\n\
-                Config file: {}\n\
-                Len            Min time(sec)   Avg time(sec)   Max time(sec)\n\
-                -------------------------------------------------------------",
-                self.bin_path.display(),
-                self.cfg_path.display()
-            );
+            let header = "Len            Min time(sec)   Avg time(sec)   Max time(sec)  User time(sec)   Sys time(sec)        RSS(KiB)   Overhead(sec)\n\
+                -----------------------------------------------------------------------------------------------------------------------------";
 
             writeln!(f, "{}", header)?;
         }
 
         {
             for run in &self.runs {
+                let line = if run.timed_out {
+                    format!("{:<12.5}{:>16}", run.len, "timed out")
+                } else {
+                    format!(
+                        "{:<12.5}{:>16.5}{:>16.5}{:>16.5}{:>16.5}{:>16.5}{:>16}{:>16.5}",
+                        run.len,
+                        run.min,
+                        run.avg,
+                        run.max,
+                        run.user,
+                        run.sys,
+                        run.rss,
+                        run.overhead
+                    )
+                };
+
+                writeln!(f, "{}", line)?;
+            }
+        }
+
+        {
+            let header = format!(
+                "Statistics ({})\n\
+                Len                   Median             P90             P99         Std dev             MAD              CV           Iters        Rejected\n\
+                -------------------------------------------------------------------------------------------------------------------------------------------",
+                self.metric
+            );
+
+            writeln!(f, "{}", header)?;
+
+            for run in self.runs.iter().filter(|run| !run.timed_out) {
                 let line = format!(
-                    "{:<12.5}{:>16.5}{:>16.5}{:>16.5}",
-                    run.len, run.min, run.avg, run.max
+                    "{:<12.5}{:>16.5}{:>16.5}{:>16.5}{:>16.5}{:>16.5}{:>16.5}{:>16}{:>16}",
+                    run.len,
+                    run.statistic(self.metric, Statistic::Median),
+                    run.statistic(self.metric, Statistic::P90),
+                    run.statistic(self.metric, Statistic::P99),
+                    run.std_dev(self.metric),
+                    run.mad(self.metric),
+                    run.cv(self.metric),
+                    run.iters(),
+                    run.rejected
                 );
 
                 writeln!(f, "{}", line)?;
             }
         }
 
-        let complexity = format!(
-            "Complexity: {} {}\nRMS: {:.2}%",
-            self.coef,
-            self.complexity,
-            self.rms * 100.0
-        );
+        if self.runs.iter().any(|run| !run.warmup.is_empty()) {
+            let header = "Warm-up (wall time)\n\
+                Len         Runs(sec)\n\
+                -----------------------------------------------------------------------------------------------------------------------------";
+
+            writeln!(f, "{}", header)?;
+
+            for run in self.runs.iter().filter(|run| !run.warmup.is_empty()) {
+                let times: Vec<String> = run.warmup.iter().map(|x| format!("{:.5}", x)).collect();
+                writeln!(f, "{:<12.5}{}", run.len, times.join(" "))?;
+            }
+        }
+
+        let total = if self.variables.len() > 1 {
+            TOTAL_NAME
+        } else {
+            "N"
+        };
+        write_fit(
+            f,
+            &format!("Time complexity ({}, {})", self.metric, self.statistic),
+            &self.time,
+            total,
+        )?;
+        writeln!(f)?;
+        write_fit(f, "Space complexity", &self.space, total)
+    }
+}
+
+/// Вывод асимптотической сложности `fit` с заголовком `title`.
+/// Суммарная длина аргументов в сложностях от одной переменной называется `total`.
+fn write_fit(f: &mut Formatter<'_>, title: &str, fit: &LeastSquares, total: &str) -> fmt::Result {
+    let name = |complexity: Complexity| complexity.name_with(total);
+
+    write!(f, "{}: {} {}", title, fit.coef, name(fit.complexity))?;
+    if fit.offset != 0.0 {
+        write!(f, " + {}", fit.offset)?;
+    }
+
+    write!(f, "\nRMS: {:.2}%", fit.rms * 100.0)?;
+    if let Some(second) = fit.ambiguous {
+        write!(
+            f,
+            "\nAmbiguous: {} vs {}",
+            name(fit.complexity),
+            name(second)
+        )?;
+    }
+    if let Some(power_law) = fit.power_law {
+        write!(
+            f,
+            "\nEmpirical order: {}",
+            power_law.to_string().replace('N', total)
+        )?;
+    }
 
-        write!(f, "{}", complexity)
+    if let Some(bootstrap) = &fit.bootstrap {
+        for (complexity, wins) in &bootstrap.wins {
+            write!(
+                f,
+                "\n{} chosen in {:.0}% of {} resamples",
+                name(*complexity),
+                *wins as f64 / bootstrap.resamples as f64 * 100.0,
+                bootstrap.resamples
+            )?;
+        }
+        write!(
+            f,
+            "\nCoefficient {:.0}% CI: [{}, {}]",
+            bootstrap.confidence_level * 100.0,
+            bootstrap.coef_interval.0,
+            bootstrap.coef_interval.1
+        )?;
     }
+
+    write!(f, "\nCandidates:")?;
+    for candidate in &fit.candidates {
+        write!(
+            f,
+            "\n  {:<12}RMS: {:>8.2}%   Confidence: {:>6.2}%",
+            name(candidate.complexity),
+            candidate.rms * 100.0,
+            candidate.confidence * 100.0
+        )?;
+        if let Some(ic) = candidate.ic {
+            write!(f, "   {}: {:>10.2}", fit.selection, ic)?;
+        }
+    }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::complexity::{Complexity, LeastSquares};
+    use crate::complexity::{Bootstrap, Candidate, Complexity, LeastSquares, PowerLaw, Selection};
     use crate::report::Report;
-    use crate::run::Run;
+    use crate::run::{Metric, Run, Sample, Statistic};
 
     fn new_report() -> Report {
-        let run1 = Run::default();
-
-        let run2 = Run::default();
+        let run = |len: usize, wall: f64| {
+            let mut run = Run {
+                len,
+                ..Run::default()
+            };
+            run.update(&Sample {
+                wall,
+                user: wall / 2.0,
+                sys: wall / 4.0,
+                rss: 4096,
+            });
+            run
+        };
+        let run1 = run(1000, 0.01);
+        let run2 = run(2000, 0.02);
 
-        let squares = LeastSquares {
+        let time = LeastSquares {
             coef: 10.0,
+            offset: 0.5,
             complexity: Complexity::ON,
             rms: 0.23,
+            power_law: Some(PowerLaw {
+                exponent: 0.98,
+                r_squared: 0.997,
+            }),
+            candidates: vec![
+                Candidate {
+                    complexity: Complexity::ON,
+                    coef: 10.0,
+                    offset: 0.5,
+                    rms: 0.23,
+                    confidence: 0.6,
+                    ic: Some(-23.5),
+                },
+                Candidate {
+                    complexity: Complexity::ONLogN,
+                    coef: 1.0,
+                    offset: 0.5,
+                    rms: 0.24,
+                    confidence: 0.4,
+                    ic: Some(-22.7),
+                },
+            ],
+            ambiguous: Some(Complexity::ONLogN),
+            bootstrap: Some(Bootstrap {
+                resamples: 1000,
+                confidence_level: 0.95,
+                coef_interval: (9.5, 10.5),
+                wins: vec![(Complexity::ON, 940), (Complexity::ONLogN, 60)],
+            }),
+            selection: Selection::Aic,
+        };
+
+        let space = LeastSquares {
+            coef: 4096.0,
+            offset: 0.0,
+            complexity: Complexity::O1,
+            rms: 0.01,
+            power_law: None,
+            candidates: Vec::new(),
+            ambiguous: None,
+            bootstrap: None,
+            selection: Selection::Rms,
         };
 
-        let report = Report::new(
+        Report::new(
             "some patj/asdkgsi/123.txt",
             "some/path.json",
             vec![run1, run2],
-            squares,
+            Metric::Wall,
+            Statistic::Median,
+            time,
+            space,
+        )
+        .with_seed(42)
+        .with_variables(vec![
+            "S = arg0 rows·columns".to_string(),
+            "N = arg0 rows".to_string(),
+            "M = arg0 columns".to_string(),
+        ])
+    }
+
+    #[test]
+    fn display_report() {
+        let report = new_report().to_string();
+        let lines: Vec<&str> = report.lines().collect();
+
+        assert!(lines.contains(&"Seed: 42"));
+        assert!(
+            lines.contains(&"Variables: S = arg0 rows·columns, N = arg0 rows, M = arg0 columns")
         );
+        assert!(lines
+            .iter()
+            .any(|l| l.starts_with("1000") && l.contains("0.01000")));
+
+        //Временная сложность: коэффициент, смещение, ошибка, неоднозначность, бутстреп и кандидаты
+        let time = lines
+            .iter()
+            .position(|&l| l == "Time complexity (wall time, median): 10 O(S) + 0.5")
+            .unwrap();
+        assert_eq!(lines[time + 1], "RMS: 23.00%");
+        assert_eq!(lines[time + 2], "Ambiguous: O(S) vs O(SlogS)");
+        assert_eq!(lines[time + 3], "Empirical order: S^0.98 (R²=0.997)");
+        assert_eq!(lines[time + 4], "O(S) chosen in 94% of 1000 resamples");
+        assert_eq!(lines[time + 5], "O(SlogS) chosen in 6% of 1000 resamples");
+        assert_eq!(lines[time + 6], "Coefficient 95% CI: [9.5, 10.5]");
+        assert!(lines[time + 8].contains("O(S)") && lines[time + 8].contains("AIC:     -23.50"));
 
-        report
+        //Сложность по памяти со своими коэффициентом и ошибкой
+        let space = lines
+            .iter()
+            .position(|&l| l == "Space complexity: 4096 O(1)")
+            .unwrap();
+        assert!(space > time);
+        assert_eq!(lines[space + 1], "RMS: 1.00%");
+        assert!(!lines[space..].iter().any(|l| l.starts_with("Ambiguous")));
     }
 
     #[test]
-    fn display_report() {
-        println!("{}", new_report());
+    fn display_report_single_variable() {
+        let report = new_report()
+            .with_variables(vec!["N = arg0 len".to_string()])
+            .to_string();
+
+        assert!(!report.contains("Variables:"));
+        assert!(report.contains("Time complexity (wall time, median): 10 O(N) + 0.5"));
+        assert!(report.contains("Ambiguous: O(N) vs O(NlogN)"));
     }
 
     #[test]
     fn debug_report() {
-        println!("{:?}", new_report());
+        let report = format!("{:?}", new_report());
+
+        assert!(report.starts_with("Report {"));
+        assert!(report.contains("complexity: ON"));
+        assert!(report.contains("seed: Some(42)"));
     }
 }
@@ -2,6 +2,25 @@
 
 use core::default::Default;
 use core::fmt::Debug;
+use rand::Rng;
+use serde::Deserialize;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+use validator::{Validate, ValidationError, ValidationErrors};
+
+/// Замер одного запуска программы.
+#[derive(Copy, Clone, PartialOrd, PartialEq, Debug, Default)]
+pub struct Sample {
+    /// Время выполнения.
+    pub wall: f64,
+    /// Процессорное время в пользовательском режиме.
+    pub user: f64,
+    /// Процессорное время в режиме ядра.
+    pub sys: f64,
+    /// Пиковый размер резидентной памяти в КиБ.
+    pub rss: u64,
+}
 
 /// Статистика времени выполнения программы в поколении.
 #[derive(PartialOrd, PartialEq, Debug)]
@@ -12,16 +31,163 @@ pub struct Run {
     pub max: f64,
     /// Среднее время.
     pub avg: f64,
+    /// Минимальное процессорное время в пользовательском режиме.
+    pub user: f64,
+    /// Минимальное процессорное время в режиме ядра.
+    pub sys: f64,
+    /// Пиковый размер резидентной памяти в КиБ.
+    pub rss: u64,
+    /// Время холостого запуска, вычтенное из замеров.
+    pub overhead: f64,
     /// Длина аргументов.
     pub len: usize,
+    /// Размеры аргументов по переменным. Матрица дает две переменные: строки и столбцы.
+    pub sizes: Vec<usize>,
+    /// Поколение прервано из-за превышения ограничения времени.
+    pub timed_out: bool,
+    /// Замеры всех запусков поколения.
+    pub samples: Vec<Sample>,
+    /// Время прогревочных запусков. В статистику не входит.
+    pub warmup: Vec<f64>,
+    /// Количество замеров, отброшенных как выбросы.
+    pub rejected: usize,
 }
 
 impl Run {
-    /// Обновляет `self`, в зависимости от `duration`.
-    pub fn update(&mut self, duration: f64) {
-        self.min = self.min.min(duration);
-        self.max = self.max.max(duration);
-        self.avg += duration;
+    /// Обновляет `self`, в зависимости от `sample`.
+    pub fn update(&mut self, sample: &Sample) {
+        self.min = self.min.min(sample.wall);
+        self.max = self.max.max(sample.wall);
+        self.avg += sample.wall;
+        self.user = self.user.min(sample.user);
+        self.sys = self.sys.min(sample.sys);
+        self.rss = self.rss.max(sample.rss);
+        self.samples.push(*sample);
+    }
+
+    /// Создает поколение той же длины из `samples.len()` случайных замеров `self` с повторениями.
+    /// Используется для бутстрепа.
+    pub fn resample<R: Rng>(&self, rng: &mut R) -> Run {
+        let mut run = Run {
+            len: self.len,
+            sizes: self.sizes.clone(),
+            overhead: self.overhead,
+            timed_out: self.timed_out,
+            ..Run::default()
+        };
+
+        let count = self.samples.len();
+        for _ in 0..count {
+            run.update(&self.samples[rng.gen_range(0..count)]);
+        }
+        run.avg /= count.max(1) as f64;
+
+        run
+    }
+
+    /// Возвращает отсортированные по возрастанию значения величины `metric` всех замеров.
+    pub fn values(&self, metric: Metric) -> Vec<f64> {
+        let mut values: Vec<f64> = self.samples.iter().map(|s| metric.value(s)).collect();
+        values.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        values
+    }
+
+    /// Вычисляет статистику `statistic` величины `metric` по всем замерам.
+    pub fn statistic(&self, metric: Metric, statistic: Statistic) -> f64 {
+        statistic.value(&self.values(metric))
+    }
+
+    /// Выборочное стандартное отклонение величины `metric`. Равно 0, если замеров меньше двух.
+    pub fn std_dev(&self, metric: Metric) -> f64 {
+        let values = self.values(metric);
+        if values.len() < 2 {
+            return 0.0;
+        }
+
+        let mean = mean(&values);
+        let sum: f64 = values.iter().map(|v| (v - mean).powi(2)).sum();
+
+        (sum / (values.len() - 1) as f64).sqrt()
+    }
+
+    /// Медианное абсолютное отклонение величины `metric`.
+    pub fn mad(&self, metric: Metric) -> f64 {
+        mad(&self.values(metric))
+    }
+
+    /// Коэффициент вариации величины `metric`: отношение стандартного отклонения к среднему.
+    pub fn cv(&self, metric: Metric) -> f64 {
+        self.std_dev(metric) / mean(&self.values(metric))
+    }
+
+    /// Количество замеров в поколении.
+    pub fn iters(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Относительная полуширина доверительного интервала уровня `level` статистики `statistic` величины `metric`.
+    /// Интервал оценивается бутстрепом из `resamples` повторных выборок.
+    pub fn relative_ci<R: Rng>(
+        &self,
+        metric: Metric,
+        statistic: Statistic,
+        level: f64,
+        resamples: usize,
+        rng: &mut R,
+    ) -> f64 {
+        let mut estimates: Vec<f64> = (0..resamples)
+            .map(|_| self.resample(rng).statistic(metric, statistic))
+            .collect();
+        estimates.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+        let alpha = 1.0 - level;
+        let width = percentile(&estimates, 1.0 - alpha / 2.0) - percentile(&estimates, alpha / 2.0);
+
+        width / 2.0 / self.statistic(metric, statistic).abs()
+    }
+
+    /// Отбрасывает замеры, величина `metric` которых выходит за границы `outliers`,
+    /// и пересчитывает статистику по оставшимся. Среднее, как и в [`Run::update`], не делится на количество замеров.
+    pub fn reject_outliers(&mut self, metric: Metric, outliers: Outliers) {
+        let (low, high) = outliers.bounds(&self.values(metric));
+        let samples = std::mem::take(&mut self.samples);
+        let (kept, rejected): (Vec<Sample>, Vec<Sample>) = samples.into_iter().partition(|s| {
+            let value = metric.value(s);
+            low <= value && value <= high
+        });
+
+        *self = Run {
+            len: self.len,
+            sizes: std::mem::take(&mut self.sizes),
+            overhead: self.overhead,
+            timed_out: self.timed_out,
+            warmup: std::mem::take(&mut self.warmup),
+            rejected: self.rejected + rejected.len(),
+            ..Run::default()
+        };
+        for sample in &kept {
+            self.update(sample);
+        }
+    }
+
+    /// Вычитает из времени `self` и его замеров минимальное время холостых запусков `overhead`.
+    /// Время не может стать отрицательным. Память не изменяется.
+    pub fn subtract_overhead(&mut self, overhead: &Run) {
+        let subtract = |time: f64, overhead: f64| (time - overhead).max(0.0);
+
+        self.min = subtract(self.min, overhead.min);
+        self.max = subtract(self.max, overhead.min);
+        self.avg = subtract(self.avg, overhead.min);
+        self.user = subtract(self.user, overhead.user);
+        self.sys = subtract(self.sys, overhead.sys);
+        self.overhead = overhead.min;
+
+        for sample in &mut self.samples {
+            sample.wall = subtract(sample.wall, overhead.min);
+            sample.user = subtract(sample.user, overhead.user);
+            sample.sys = subtract(sample.sys, overhead.sys);
+        }
     }
 }
 
@@ -31,28 +197,484 @@ impl Default for Run {
             min: f64::MAX,
             max: f64::MIN,
             avg: 0.0,
+            user: f64::MAX,
+            sys: f64::MAX,
+            rss: 0,
+            overhead: 0.0,
             len: 0,
+            sizes: Vec::new(),
+            timed_out: false,
+            samples: Vec::new(),
+            warmup: Vec::new(),
+            rejected: 0,
+        }
+    }
+}
+
+/// Возвращает среднее значение `values`.
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Медианное абсолютное отклонение отсортированных по возрастанию значений `sorted`.
+fn mad(sorted: &[f64]) -> f64 {
+    let median = percentile(sorted, 0.5);
+
+    let mut deviations: Vec<f64> = sorted.iter().map(|v| (v - median).abs()).collect();
+    deviations.sort_by(|a, b| a.partial_cmp(b).unwrap());
+
+    percentile(&deviations, 0.5)
+}
+
+/// Возвращает квантиль `q` из [0, 1] отсортированного по возрастанию `sorted` с линейной интерполяцией.
+pub fn percentile(sorted: &[f64], q: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+
+    let pos = q * (sorted.len() - 1) as f64;
+    let (low, high) = (pos.floor() as usize, pos.ceil() as usize);
+
+    sorted[low] + (sorted[high] - sorted[low]) * (pos - low as f64)
+}
+
+/// Способ обнаружения выбросов среди замеров поколения.
+#[derive(Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum Outliers {
+    /// Границы Тьюки: значения вне `[Q1 - k * IQR, Q3 + k * IQR]`. Обычно k = 1.5.
+    Tukey(f64),
+    /// Модифицированная z-оценка: значения, у которых `0.6745 * |x - median| / MAD` больше порога. Обычно 3.5.
+    ModifiedZScore(f64),
+}
+
+impl Outliers {
+    /// Возвращает границы допустимых значений для отсортированных по возрастанию `sorted`.
+    /// Если разброс нулевой, выбросов нет.
+    pub fn bounds(self, sorted: &[f64]) -> (f64, f64) {
+        let (low, high) = match self {
+            Outliers::Tukey(k) => {
+                let (q1, q3) = (percentile(sorted, 0.25), percentile(sorted, 0.75));
+                let iqr = q3 - q1;
+                (q1 - k * iqr, q3 + k * iqr)
+            }
+            Outliers::ModifiedZScore(threshold) => {
+                let median = percentile(sorted, 0.5);
+                let radius = threshold * mad(sorted) / 0.6745;
+                (median - radius, median + radius)
+            }
+        };
+
+        if low < high {
+            (low, high)
+        } else {
+            (f64::NEG_INFINITY, f64::INFINITY)
+        }
+    }
+}
+
+impl Validate for Outliers {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        match self {
+            Outliers::Tukey(x) | Outliers::ModifiedZScore(x) if *x <= 0.0 => {
+                let mut errors = ValidationErrors::new();
+                let mut error = ValidationError::new("threshold <= 0");
+                error.add_param("threshold".into(), x);
+                errors.add("Outliers", error);
+
+                Err(errors)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Величина, по которой вычисляется временная сложность.
+#[derive(Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum Metric {
+    /// Время выполнения.
+    Wall,
+    /// Процессорное время в пользовательском режиме.
+    User,
+    /// Процессорное время в режиме ядра.
+    System,
+    /// Суммарное процессорное время.
+    Cpu,
+}
+
+impl Default for Metric {
+    fn default() -> Self {
+        Metric::Wall
+    }
+}
+
+impl Metric {
+    /// Возвращает значение величины в замере `sample`.
+    pub fn value(self, sample: &Sample) -> f64 {
+        match self {
+            Metric::Wall => sample.wall,
+            Metric::User => sample.user,
+            Metric::System => sample.sys,
+            Metric::Cpu => sample.user + sample.sys,
+        }
+    }
+}
+
+impl Display for Metric {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let metric_str = match self {
+            Metric::Wall => "wall time",
+            Metric::User => "user time",
+            Metric::System => "system time",
+            Metric::Cpu => "cpu time",
+        };
+
+        write!(f, "{}", metric_str)
+    }
+}
+
+/// Статистика замеров поколения, по которой вычисляется сложность.
+#[derive(Deserialize, Copy, Clone, PartialEq, Debug)]
+pub enum Statistic {
+    /// Минимальное значение.
+    Min,
+    /// Среднее значение.
+    Mean,
+    /// Медиана.
+    Median,
+    /// 90-й перцентиль.
+    P90,
+    /// 99-й перцентиль.
+    P99,
+    /// Максимальное значение.
+    Max,
+    /// Среднее значение после отбрасывания доли `[0, 0.5)` наименьших и наибольших значений.
+    TrimmedMean(f64),
+}
+
+impl Default for Statistic {
+    fn default() -> Self {
+        Statistic::Min
+    }
+}
+
+impl Statistic {
+    /// Вычисляет статистику отсортированных по возрастанию значений `sorted`.
+    pub fn value(self, sorted: &[f64]) -> f64 {
+        match self {
+            Statistic::Min => percentile(sorted, 0.0),
+            Statistic::Mean => mean(sorted),
+            Statistic::Median => percentile(sorted, 0.5),
+            Statistic::P90 => percentile(sorted, 0.9),
+            Statistic::P99 => percentile(sorted, 0.99),
+            Statistic::Max => percentile(sorted, 1.0),
+            Statistic::TrimmedMean(fraction) => {
+                let trim = (sorted.len() as f64 * fraction) as usize;
+                match sorted.get(trim..sorted.len() - trim) {
+                    Some(trimmed) if !trimmed.is_empty() => mean(trimmed),
+                    _ => percentile(sorted, 0.5),
+                }
+            }
+        }
+    }
+
+    /// Доля отбрасываемых значений [`Statistic::TrimmedMean`] по умолчанию.
+    const TRIM_DEFAULT: f64 = 0.1;
+}
+
+impl FromStr for Statistic {
+    type Err = String;
+
+    /// Разбирает статистику из строки: `min`, `mean`, `median`, `p90`, `p99`, `max`,
+    /// `trimmed-mean` или `trimmed-mean:<доля>`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        let (name, param) = match lower.find(':') {
+            Some(pos) => (&lower[..pos], Some(&lower[pos + 1..])),
+            None => (lower.as_str(), None),
+        };
+
+        let statistic = match (name, param) {
+            ("min", None) => Statistic::Min,
+            ("mean", None) => Statistic::Mean,
+            ("median", None) => Statistic::Median,
+            ("p90", None) => Statistic::P90,
+            ("p99", None) => Statistic::P99,
+            ("max", None) => Statistic::Max,
+            ("trimmed-mean", None) => Statistic::TrimmedMean(Statistic::TRIM_DEFAULT),
+            ("trimmed-mean", Some(fraction)) => {
+                let fraction = fraction
+                    .parse()
+                    .map_err(|_| format!("Invalid trim fraction '{}'", fraction))?;
+                Statistic::TrimmedMean(fraction)
+            }
+            _ => return Err(format!("Unknown statistic '{}'", s)),
+        };
+
+        statistic
+            .validate()
+            .map_err(|_| format!("Trim fraction must be in [0, 0.5). '{}'", s))?;
+
+        Ok(statistic)
+    }
+}
+
+impl Validate for Statistic {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        match self {
+            Statistic::TrimmedMean(fraction) if !(0.0..0.5).contains(fraction) => {
+                let mut errors = ValidationErrors::new();
+                let mut error = ValidationError::new("fraction not in [0, 0.5)");
+                error.add_param("fraction".into(), fraction);
+                errors.add("Statistic::TrimmedMean", error);
+
+                Err(errors)
+            }
+            _ => Ok(()),
         }
     }
 }
 
+impl Display for Statistic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let statistic_str = match self {
+            Statistic::Min => "min",
+            Statistic::Mean => "mean",
+            Statistic::Median => "median",
+            Statistic::P90 => "p90",
+            Statistic::P99 => "p99",
+            Statistic::Max => "max",
+            Statistic::TrimmedMean(fraction) => {
+                return write!(f, "{}% trimmed mean", fraction * 100.0)
+            }
+        };
+
+        write!(f, "{}", statistic_str)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::run::Run;
+    use crate::run::{percentile, Metric, Outliers, Run, Sample, Statistic};
+    use validator::Validate;
 
     #[test]
     fn update_test() {
         let mut run = Run::default();
-        run.update(10.0);
+        let sample = Sample {
+            wall: 10.0,
+            user: 6.0,
+            sys: 2.0,
+            rss: 1024,
+        };
+        run.update(&sample);
 
         assert_eq!(
             Run {
                 min: 10.0,
                 max: 10.0,
                 avg: 10.0,
+                user: 6.0,
+                sys: 2.0,
+                rss: 1024,
+                overhead: 0.0,
                 len: 0,
+                sizes: Vec::new(),
+                timed_out: false,
+                samples: vec![sample],
+                warmup: Vec::new(),
+                rejected: 0,
             },
             run
-        )
+        );
+        assert_eq!(run.statistic(Metric::Cpu, Statistic::Min), 8.0);
+    }
+
+    #[test]
+    fn update_several_test() {
+        let samples = [
+            Sample {
+                wall: 3.0,
+                user: 2.0,
+                sys: 0.5,
+                rss: 2048,
+            },
+            Sample {
+                wall: 1.0,
+                user: 0.75,
+                sys: 0.25,
+                rss: 4096,
+            },
+            Sample {
+                wall: 2.0,
+                user: 1.0,
+                sys: 0.125,
+                rss: 1024,
+            },
+        ];
+        let mut run = Run::default();
+        for sample in &samples {
+            run.update(sample);
+        }
+
+        assert_eq!((run.min, run.max, run.avg), (1.0, 3.0, 6.0));
+        //Процессорное время - минимальное, память - пиковая
+        assert_eq!((run.user, run.sys, run.rss), (0.75, 0.125, 4096));
+        assert_eq!(run.iters(), 3);
+
+        let metric =
+            |metric: Metric| -> Vec<f64> { samples.iter().map(|s| metric.value(s)).collect() };
+        assert_eq!(metric(Metric::Wall), [3.0, 1.0, 2.0]);
+        assert_eq!(metric(Metric::User), [2.0, 0.75, 1.0]);
+        assert_eq!(metric(Metric::System), [0.5, 0.25, 0.125]);
+        assert_eq!(metric(Metric::Cpu), [2.5, 1.0, 1.125]);
+        assert_eq!(run.statistic(Metric::Cpu, Statistic::Max), 2.5);
+    }
+
+    #[test]
+    fn subtract_overhead_test() {
+        let mut run = Run {
+            min: 3.0,
+            max: 5.0,
+            avg: 4.0,
+            user: 1.0,
+            sys: 0.5,
+            ..Run::default()
+        };
+        let overhead = Run {
+            min: 1.0,
+            user: 0.25,
+            sys: 1.0,
+            ..Run::default()
+        };
+        run.subtract_overhead(&overhead);
+
+        assert_eq!((run.min, run.max, run.avg), (2.0, 4.0, 3.0));
+        assert_eq!((run.user, run.sys, run.overhead), (0.75, 0.0, 1.0));
+    }
+
+    #[test]
+    fn resample_test() {
+        let mut run = Run {
+            len: 10,
+            ..Run::default()
+        };
+        for wall in &[1.0, 2.0, 3.0] {
+            run.update(&Sample {
+                wall: *wall,
+                ..Sample::default()
+            });
+        }
+
+        let resampled = run.resample(&mut rand::thread_rng());
+
+        assert_eq!(resampled.len, 10);
+        assert_eq!(resampled.samples.len(), 3);
+        assert!(resampled.min >= 1.0 && resampled.max <= 3.0);
+    }
+
+    #[test]
+    fn percentile_test() {
+        let sorted = [1.0, 2.0, 3.0, 4.0, 5.0];
+
+        assert_eq!(percentile(&sorted, 0.0), 1.0);
+        assert_eq!(percentile(&sorted, 0.5), 3.0);
+        assert_eq!(percentile(&sorted, 0.625), 3.5);
+        assert_eq!(percentile(&sorted, 1.0), 5.0);
+    }
+
+    #[test]
+    fn statistics_test() {
+        let mut run = Run::default();
+        for wall in &[4.0, 1.0, 2.0, 3.0, 10.0] {
+            run.update(&Sample {
+                wall: *wall,
+                ..Sample::default()
+            });
+        }
+
+        assert_eq!(run.values(Metric::Wall), vec![1.0, 2.0, 3.0, 4.0, 10.0]);
+        assert_eq!(run.statistic(Metric::Wall, Statistic::Median), 3.0);
+        assert_eq!(run.statistic(Metric::Wall, Statistic::Mean), 4.0);
+        assert!((run.statistic(Metric::Wall, Statistic::P90) - 7.6).abs() < 1e-12);
+        assert_eq!(run.statistic(Metric::Wall, Statistic::Max), 10.0);
+        assert_eq!(run.mad(Metric::Wall), 1.0);
+        assert!((run.std_dev(Metric::Wall) - 12.5f64.sqrt()).abs() < 1e-12);
+        assert!((run.cv(Metric::Wall) - 12.5f64.sqrt() / 4.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn trimmed_mean_test() {
+        let sorted = [1.0, 2.0, 3.0, 4.0, 100.0];
+
+        assert_eq!(Statistic::TrimmedMean(0.2).value(&sorted), 3.0);
+        assert_eq!(Statistic::TrimmedMean(0.0).value(&sorted), 22.0);
+        assert_eq!(Statistic::TrimmedMean(0.1).value(&sorted[..1]), 1.0);
+    }
+
+    #[test]
+    fn statistic_from_str_test() {
+        assert_eq!("Median".parse(), Ok(Statistic::Median));
+        assert_eq!("trimmed-mean".parse(), Ok(Statistic::TrimmedMean(0.1)));
+        assert_eq!(
+            "trimmed-mean:0.25".parse(),
+            Ok(Statistic::TrimmedMean(0.25))
+        );
+        assert!("trimmed-mean:0.5".parse::<Statistic>().is_err());
+        assert!("p50".parse::<Statistic>().is_err());
+    }
+
+    #[test]
+    fn relative_ci_test() {
+        let mut run = Run::default();
+        for _ in 0..10 {
+            run.update(&Sample {
+                wall: 2.0,
+                ..Sample::default()
+            });
+        }
+        let mut rng = rand::thread_rng();
+
+        assert_eq!(run.iters(), 10);
+        assert_eq!(
+            run.relative_ci(Metric::Wall, Statistic::Mean, 0.95, 100, &mut rng),
+            0.0
+        );
+
+        run.update(&Sample {
+            wall: 4.0,
+            ..Sample::default()
+        });
+        assert!(run.relative_ci(Metric::Wall, Statistic::Mean, 0.95, 100, &mut rng) > 0.0);
+    }
+
+    #[test]
+    fn reject_outliers_test() {
+        let walls = [1.0, 1.1, 0.9, 1.05, 0.95, 10.0];
+        for outliers in [Outliers::Tukey(1.5), Outliers::ModifiedZScore(3.5)] {
+            let mut run = Run::default();
+            for &wall in &walls {
+                run.update(&Sample {
+                    wall,
+                    ..Sample::default()
+                });
+            }
+            run.reject_outliers(Metric::Wall, outliers);
+
+            assert_eq!(run.rejected, 1);
+            assert_eq!(run.iters(), 5);
+            assert_eq!(run.max, 1.1);
+            assert!((run.avg - 5.0).abs() < 1e-9);
+        }
+
+        let mut run = Run::default();
+        for _ in 0..5 {
+            run.update(&Sample {
+                wall: 2.0,
+                ..Sample::default()
+            });
+        }
+        run.reject_outliers(Metric::Wall, Outliers::ModifiedZScore(3.5));
+        assert_eq!(run.rejected, 0);
+        assert!(Outliers::Tukey(0.0).validate().is_err());
     }
 }